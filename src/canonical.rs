@@ -0,0 +1,434 @@
+use std::collections::HashMap;
+
+use fxhash::FxHashMap;
+
+use crate::{ConGraph, DefaultNodeID, HGraph, HgNode};
+
+/// The output of the canonical labeling search of a hypergraph. Two
+/// hypergraphs are isomorphic if and only if relabeling their nodes by
+/// their respective `order`s yields identical edge sets.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CanonicalLabeling<NodeID: HgNode> {
+    /// `order[i]` is the node that receives the canonical label `i`.
+    pub order: Vec<NodeID>,
+    /// Generators of the automorphism group of the hypergraph that were
+    /// discovered during the search. Each generator is a permutation of the
+    /// nodes, stored as a map from a node to its image with fixed points
+    /// omitted. An empty vec means the hypergraph has no symmetries.
+    pub automorphism_generators: Vec<HashMap<NodeID, NodeID>>,
+}
+
+/// Dense incidence structure used by the search, nodes and edges are
+/// referred to by their position.
+struct Incidence {
+    edges: Vec<Vec<usize>>,
+    node_edges: Vec<Vec<usize>>,
+}
+
+/// Replaces each signature by its rank among the distinct signatures. As
+/// long as the signatures are isomorphism invariant so are the ranks.
+fn rank<T: Ord + Clone>(signatures: &[T]) -> Vec<usize> {
+    let mut distinct = signatures.to_vec();
+    distinct.sort();
+    distinct.dedup();
+    signatures
+        .iter()
+        .map(|sig| {
+            distinct
+                .binary_search(sig)
+                .expect("Signature must be present.")
+        })
+        .collect()
+}
+
+fn num_colors(colors: &[usize]) -> usize {
+    colors.iter().max().map_or(0, |max| max + 1)
+}
+
+impl Incidence {
+    /// Color refinement on the bipartite node-edge incidence graph until the
+    /// coloring of the nodes is stable. Cells are only ever split, and the
+    /// relative order of existing cells is preserved.
+    fn refine(&self, mut colors: Vec<usize>) -> Vec<usize> {
+        loop {
+            let edge_signatures: Vec<Vec<usize>> = self
+                .edges
+                .iter()
+                .map(|edge| {
+                    let mut sig: Vec<usize> = edge.iter().map(|node| colors[*node]).collect();
+                    sig.sort();
+                    sig
+                })
+                .collect();
+            let edge_colors = rank(&edge_signatures);
+            let node_signatures: Vec<(usize, Vec<usize>)> = self
+                .node_edges
+                .iter()
+                .enumerate()
+                .map(|(node, edges)| {
+                    let mut sig: Vec<usize> = edges.iter().map(|edge| edge_colors[*edge]).collect();
+                    sig.sort();
+                    (colors[node], sig)
+                })
+                .collect();
+            let new_colors = rank(&node_signatures);
+            if num_colors(&new_colors) == num_colors(&colors) {
+                return new_colors;
+            }
+            colors = new_colors;
+        }
+    }
+
+    /// Splits `node` into its own cell placed directly before the rest of
+    /// its old cell.
+    fn individualize(colors: &[usize], node: usize) -> Vec<usize> {
+        let signatures: Vec<(usize, bool)> = colors
+            .iter()
+            .enumerate()
+            .map(|(ix, color)| (*color, ix != node))
+            .collect();
+        rank(&signatures)
+    }
+
+    /// The relabeled edge set of the hypergraph for a discrete coloring.
+    fn certificate(&self, labeling: &[usize]) -> Vec<Vec<usize>> {
+        let mut cert: Vec<Vec<usize>> = self
+            .edges
+            .iter()
+            .map(|edge| {
+                let mut relabeled: Vec<usize> = edge.iter().map(|node| labeling[*node]).collect();
+                relabeled.sort();
+                relabeled
+            })
+            .collect();
+        cert.sort();
+        cert
+    }
+}
+
+/// The state of the individualization-refinement search.
+struct Search<'a> {
+    incidence: &'a Incidence,
+    first: Option<(Vec<Vec<usize>>, Vec<usize>)>,
+    best: Option<(Vec<Vec<usize>>, Vec<usize>)>,
+    automorphisms: Vec<Vec<usize>>,
+}
+
+impl<'a> Search<'a> {
+    fn new(incidence: &'a Incidence) -> Self {
+        Search {
+            incidence,
+            first: None,
+            best: None,
+            automorphisms: Vec::new(),
+        }
+    }
+
+    fn explore(&mut self, colors: Vec<usize>, path: &mut Vec<usize>) {
+        let colors = self.incidence.refine(colors);
+        let n = colors.len();
+        if num_colors(&colors) == n {
+            self.leaf(colors);
+            return;
+        }
+        let mut cell_sizes = vec![0; n];
+        for color in colors.iter() {
+            cell_sizes[*color] += 1;
+        }
+        let target = (0..n)
+            .find(|color| cell_sizes[*color] > 1)
+            .expect("Non-discrete coloring has a non-singleton cell.");
+        let cell: Vec<usize> = (0..n).filter(|node| colors[*node] == target).collect();
+        let mut explored: Vec<usize> = Vec::new();
+        for node in cell {
+            // Only automorphisms fixing the current path pointwise can be
+            // used to prune the children of this node.
+            let orbits = self.orbits_fixing(path, n);
+            if explored.iter().any(|other| orbits[*other] == orbits[node]) {
+                continue;
+            }
+            explored.push(node);
+            path.push(node);
+            self.explore(Incidence::individualize(&colors, node), path);
+            path.pop();
+        }
+    }
+
+    fn leaf(&mut self, labeling: Vec<usize>) {
+        let cert = self.incidence.certificate(&labeling);
+        if self.first.is_none() {
+            self.first = Some((cert.clone(), labeling.clone()));
+            self.best = Some((cert, labeling));
+            return;
+        }
+        for reference in [&self.first, &self.best] {
+            let (ref_cert, ref_labeling) = reference.as_ref().unwrap();
+            if *ref_cert == cert {
+                let automorphism = Self::automorphism(ref_labeling, &labeling);
+                if automorphism
+                    .iter()
+                    .enumerate()
+                    .any(|(ix, image)| ix != *image)
+                    && !self.automorphisms.contains(&automorphism)
+                {
+                    self.automorphisms.push(automorphism);
+                }
+                return;
+            }
+        }
+        if cert < self.best.as_ref().unwrap().0 {
+            self.best = Some((cert, labeling));
+        }
+    }
+
+    /// Two labelings with the same certificate differ by an automorphism,
+    /// which maps `u` to the node that `other` gives the label of `u`.
+    fn automorphism(reference: &[usize], other: &[usize]) -> Vec<usize> {
+        let mut inverse = vec![0; other.len()];
+        for (node, label) in other.iter().enumerate() {
+            inverse[*label] = node;
+        }
+        reference.iter().map(|label| inverse[*label]).collect()
+    }
+
+    /// Union-find over the automorphisms found so far that fix every node in
+    /// `path`, returning a representative for the orbit of each node.
+    fn orbits_fixing(&self, path: &[usize], n: usize) -> Vec<usize> {
+        let mut parent: Vec<usize> = (0..n).collect();
+        fn find(parent: &mut [usize], mut x: usize) -> usize {
+            while parent[x] != x {
+                parent[x] = parent[parent[x]];
+                x = parent[x];
+            }
+            x
+        }
+        for automorphism in self.automorphisms.iter() {
+            if path.iter().any(|node| automorphism[*node] != *node) {
+                continue;
+            }
+            for (node, image) in automorphism.iter().enumerate() {
+                let a = find(&mut parent, node);
+                let b = find(&mut parent, *image);
+                if a != b {
+                    parent[a.max(b)] = a.min(b);
+                }
+            }
+        }
+        (0..n).map(|node| find(&mut parent, node)).collect()
+    }
+}
+
+impl<NodeData, EdgeData, NodeID: HgNode, EdgeID: HgNode>
+    HGraph<NodeData, EdgeData, NodeID, EdgeID>
+{
+    /// Sorted node IDs along with the incidence structure in terms of their
+    /// positions.
+    fn incidence(&self) -> (Vec<NodeID>, Incidence) {
+        let mut nodes: Vec<NodeID> = self.nodes.keys().cloned().collect();
+        nodes.sort();
+        let position: FxHashMap<NodeID, usize> = nodes
+            .iter()
+            .enumerate()
+            .map(|(ix, node)| (*node, ix))
+            .collect();
+        let mut edge_ids: Vec<EdgeID> = self.edges.keys().cloned().collect();
+        edge_ids.sort();
+        let mut node_edges = vec![Vec::new(); nodes.len()];
+        let edges = edge_ids
+            .iter()
+            .enumerate()
+            .map(|(edge_ix, id)| {
+                let edge = &self.edges.get(id).unwrap().nodes;
+                edge.0
+                    .iter()
+                    .map(|node| {
+                        let node_ix = position[node];
+                        node_edges[node_ix].push(edge_ix);
+                        node_ix
+                    })
+                    .collect()
+            })
+            .collect();
+        (nodes, Incidence { edges, node_edges })
+    }
+
+    /// Computes a canonical ordering of the nodes along with generators of the
+    /// automorphism group, using an individualization-refinement search over
+    /// the bipartite incidence graph of nodes and edges. The search is
+    /// exponential in the worst case, so this is meant for small hypergraphs.
+    pub fn canonical_labeling(&self) -> CanonicalLabeling<NodeID> {
+        let (nodes, incidence) = self.incidence();
+        if nodes.is_empty() {
+            return CanonicalLabeling {
+                order: Vec::new(),
+                automorphism_generators: Vec::new(),
+            };
+        }
+        let mut search = Search::new(&incidence);
+        search.explore(vec![0; nodes.len()], &mut Vec::new());
+        let (_, labeling) = search.best.expect("Search always reaches a leaf.");
+        let mut order = vec![nodes[0]; nodes.len()];
+        for (node_ix, label) in labeling.iter().enumerate() {
+            order[*label] = nodes[node_ix];
+        }
+        let automorphism_generators = search
+            .automorphisms
+            .into_iter()
+            .map(|automorphism| {
+                automorphism
+                    .into_iter()
+                    .enumerate()
+                    .filter(|(node, image)| node != image)
+                    .map(|(node, image)| (nodes[node], nodes[image]))
+                    .collect()
+            })
+            .collect();
+        CanonicalLabeling {
+            order,
+            automorphism_generators,
+        }
+    }
+
+    /// Relabels the nodes by their [`canonical_labeling`](HGraph::canonical_labeling)
+    /// and returns the result as a `ConGraph` with the default ID types. Node
+    /// `i` of the output is `order[i]` and edges are added in sorted order, so
+    /// isomorphic hypergraphs produce equal `ConGraph`s, including the edge
+    /// IDs.
+    pub fn canonical_form(&self) -> ConGraph {
+        let labeling = self.canonical_labeling();
+        let mut canonical = ConGraph::new();
+        let labels = canonical.add_nodes(labeling.order.len());
+        let label: FxHashMap<NodeID, DefaultNodeID> = labeling
+            .order
+            .iter()
+            .zip(labels)
+            .map(|(node, label)| (*node, label))
+            .collect();
+        let mut edges: Vec<Vec<DefaultNodeID>> = self
+            .edges
+            .values()
+            .map(|edge| {
+                let mut relabeled: Vec<DefaultNodeID> =
+                    edge.nodes.0.iter().map(|node| label[node]).collect();
+                relabeled.sort();
+                relabeled
+            })
+            .collect();
+        edges.sort();
+        for edge in edges {
+            canonical.add_edge(edge);
+        }
+        canonical
+    }
+
+    /// A Weisfeiler-Lehman style hash of the hypergraph, computed by
+    /// `iterations` rounds of color refinement on the bipartite incidence
+    /// graph. Isomorphic hypergraphs always hash to the same value, but
+    /// non-isomorphic hypergraphs may collide, so equal hashes should be
+    /// confirmed with [`canonical_form`](HGraph::canonical_form).
+    pub fn wl_hash(&self, iterations: usize) -> u64 {
+        let (nodes, incidence) = self.incidence();
+        let mut node_colors = vec![0_u64; nodes.len()];
+        let mut edge_colors = vec![0_u64; incidence.edges.len()];
+        let mut history = vec![(nodes.len() as u64, incidence.edges.len() as u64)];
+        for _ in 0..iterations {
+            for (edge_ix, edge) in incidence.edges.iter().enumerate() {
+                let mut sig: Vec<u64> = edge.iter().map(|node| node_colors[*node]).collect();
+                sig.sort();
+                edge_colors[edge_ix] = fxhash::hash64(&sig);
+            }
+            for (node_ix, edges) in incidence.node_edges.iter().enumerate() {
+                let mut sig: Vec<u64> = edges.iter().map(|edge| edge_colors[*edge]).collect();
+                sig.sort();
+                node_colors[node_ix] = fxhash::hash64(&(node_colors[node_ix], sig));
+            }
+            let mut node_histogram = node_colors.clone();
+            node_histogram.sort();
+            let mut edge_histogram = edge_colors.clone();
+            edge_histogram.sort();
+            history.push((
+                fxhash::hash64(&node_histogram),
+                fxhash::hash64(&edge_histogram),
+            ));
+        }
+        fxhash::hash64(&history)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::HGraph;
+
+    fn relabeled_copies() -> (HGraph<(), ()>, HGraph<(), ()>) {
+        let mut a = HGraph::<(), ()>::new();
+        a.add_nodes(6);
+        a.add_edge([0, 1, 2], ());
+        a.add_edge([2, 3], ());
+        a.add_edge([3, 4, 5], ());
+        a.add_edge([0, 5], ());
+
+        // Same hypergraph under the permutation i -> 5 - i, with the edges
+        // added in a different order.
+        let mut b = HGraph::<(), ()>::new();
+        b.add_nodes(6);
+        b.add_edge([5, 0], ());
+        b.add_edge([2, 1, 0], ());
+        b.add_edge([3, 2], ());
+        b.add_edge([5, 4, 3], ());
+        (a, b)
+    }
+
+    #[test]
+    fn isomorphic_graphs_agree() {
+        let (a, b) = relabeled_copies();
+        assert_eq!(a.canonical_form(), b.canonical_form());
+        assert_eq!(a.wl_hash(3), b.wl_hash(3));
+
+        let mut c = HGraph::<(), ()>::new();
+        c.add_nodes(6);
+        c.add_edge([0, 1, 2], ());
+        c.add_edge([2, 3], ());
+        c.add_edge([3, 4, 5], ());
+        c.add_edge([2, 5], ());
+        assert_ne!(a.canonical_form(), c.canonical_form());
+    }
+
+    #[test]
+    fn automorphisms_of_triangle() {
+        let mut hg = HGraph::<(), ()>::new();
+        hg.add_nodes(3);
+        hg.add_edge([0, 1], ());
+        hg.add_edge([1, 2], ());
+        hg.add_edge([0, 2], ());
+        let labeling = hg.canonical_labeling();
+        assert_eq!(labeling.order.len(), 3);
+        let generators: Vec<Vec<u32>> = labeling
+            .automorphism_generators
+            .iter()
+            .map(|generator| {
+                (0..3)
+                    .map(|node| *generator.get(&node).unwrap_or(&node))
+                    .collect()
+            })
+            .collect();
+        for generator in generators.iter() {
+            for edge in [[0, 1], [1, 2], [0, 2]] {
+                let image: Vec<u32> = edge.iter().map(|node| generator[*node as usize]).collect();
+                assert!(hg.find_id(image).is_some());
+            }
+        }
+        // The generators should produce the full symmetric group on 3 nodes.
+        let mut group = vec![vec![0_u32, 1, 2]];
+        let mut ix = 0;
+        while ix < group.len() {
+            for generator in generators.iter() {
+                let composed: Vec<u32> = group[ix].iter().map(|x| generator[*x as usize]).collect();
+                if !group.contains(&composed) {
+                    group.push(composed);
+                }
+            }
+            ix += 1;
+        }
+        assert_eq!(group.len(), 6);
+    }
+}
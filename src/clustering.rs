@@ -0,0 +1,257 @@
+use std::collections::{BTreeMap, HashMap};
+
+use fxhash::{FxHashMap, FxHashSet};
+
+use crate::{HGraph, HgNode};
+
+impl<NodeData, EdgeData, NodeID: HgNode, EdgeID: HgNode>
+    HGraph<NodeData, EdgeData, NodeID, EdgeID>
+{
+    /// The local clustering coefficient of Estrada and Rodríguez-Velázquez.
+    /// Counts the pairs of neighbors `u, w` of `node` that are joined to it by
+    /// a hyperpath `u - e - node - f - w` with `e != f`, and returns the
+    /// fraction of those pairs that also close a hypertriangle, meaning there
+    /// is a third edge `g` containing `u` and `w` with `e, f, g` all distinct.
+    /// Unlike the clustering of the projected graph a single edge containing
+    /// all three nodes is not a triangle. Returns `None` if the node does not
+    /// exist and `0.0` if there are no hyperpaths through it.
+    pub fn local_estrada_clustering(&self, node: &NodeID) -> Option<f64> {
+        let (triangles, paths) = self.estrada_counts(node)?;
+        if paths == 0 {
+            Some(0.0)
+        } else {
+            Some(triangles as f64 / paths as f64)
+        }
+    }
+
+    /// The global clustering coefficient of Estrada and Rodríguez-Velázquez,
+    /// the number of hypertriangles at each node summed over all nodes
+    /// divided by the number of length two hyperpaths summed over all nodes.
+    /// See [`local_estrada_clustering`](HGraph::local_estrada_clustering).
+    pub fn estrada_clustering(&self) -> f64 {
+        let (triangles, paths) = self
+            .nodes
+            .keys()
+            .filter_map(|node| self.estrada_counts(node))
+            .fold((0, 0), |acc, (t, p)| (acc.0 + t, acc.1 + p));
+        if paths == 0 {
+            0.0
+        } else {
+            triangles as f64 / paths as f64
+        }
+    }
+
+    /// Returns the number of hypertriangles and length two hyperpaths
+    /// centered at `node`, both counted as unordered pairs of neighbors.
+    fn estrada_counts(&self, node: &NodeID) -> Option<(usize, usize)> {
+        let containing_edges = &self.nodes.get(node)?.containing_edges;
+        let mut shared_edges: FxHashMap<NodeID, FxHashSet<EdgeID>> = FxHashMap::default();
+        for edge_id in containing_edges.iter() {
+            for neighbor in self.edges.get(edge_id).unwrap().nodes.0.iter() {
+                if neighbor != node {
+                    shared_edges.entry(*neighbor).or_default().insert(*edge_id);
+                }
+            }
+        }
+        let mut neighbors: Vec<NodeID> = shared_edges.keys().cloned().collect();
+        neighbors.sort();
+        let mut triangles = 0;
+        let mut paths = 0;
+        for (ix, u) in neighbors.iter().enumerate() {
+            for w in neighbors[ix + 1..].iter() {
+                let a = &shared_edges[u];
+                let b = &shared_edges[w];
+                if a.union(b).count() < 2 {
+                    continue;
+                }
+                paths += 1;
+                let u_edges = &self.nodes.get(u).unwrap().containing_edges;
+                let w_edges = &self.nodes.get(w).unwrap().containing_edges;
+                let c: FxHashSet<EdgeID> = u_edges.intersection(w_edges).cloned().collect();
+                if has_distinct_representatives(a, b, &c) {
+                    triangles += 1;
+                }
+            }
+        }
+        Some((triangles, paths))
+    }
+
+    /// The local clustering coefficient of Zhou and Nakhleh, the average over
+    /// pairs of edges `e, f` containing `node` of the edge overlap
+    /// `(|e ∩ f| - 1) / |N(node)|` where `N(node)` is the set of neighbors.
+    /// Returns `None` if the node does not exist and `0.0` if it is in fewer
+    /// than two edges or has no neighbors.
+    pub fn local_zhou_clustering(&self, node: &NodeID) -> Option<f64> {
+        let containing_edges: Vec<&EdgeID> =
+            self.nodes.get(node)?.containing_edges.iter().collect();
+        let degree = containing_edges.len();
+        if degree < 2 {
+            return Some(0.0);
+        }
+        let mut neighbors = FxHashSet::default();
+        for edge_id in containing_edges.iter() {
+            neighbors.extend(self.edges.get(edge_id).unwrap().nodes.0.iter().cloned());
+        }
+        neighbors.remove(node);
+        // Only possible with duplicate singleton edges left by `remove_node`.
+        if neighbors.is_empty() {
+            return Some(0.0);
+        }
+        let mut overlap = 0;
+        for (ix, e) in containing_edges.iter().enumerate() {
+            let e_nodes = &self.edges.get(e).unwrap().nodes;
+            for f in containing_edges[ix + 1..].iter() {
+                let f_nodes = &self.edges.get(f).unwrap().nodes;
+                overlap += e_nodes.intersection(f_nodes).len() - 1;
+            }
+        }
+        let pairs = degree * (degree - 1) / 2;
+        Some(overlap as f64 / (neighbors.len() as f64 * pairs as f64))
+    }
+
+    /// The average of [`local_zhou_clustering`](HGraph::local_zhou_clustering)
+    /// over all nodes.
+    pub fn zhou_clustering(&self) -> f64 {
+        if self.nodes.is_empty() {
+            return 0.0;
+        }
+        let total: f64 = self
+            .nodes
+            .keys()
+            .filter_map(|node| self.local_zhou_clustering(node))
+            .sum();
+        total / self.nodes.len() as f64
+    }
+
+    /// The overlap coefficient `|e ∩ f| / min(|e|, |f|)` of two edges, `None`
+    /// if either edge does not exist.
+    pub fn overlap_coefficient(&self, e: &EdgeID, f: &EdgeID) -> Option<f64> {
+        let e_nodes = &self.edges.get(e)?.nodes;
+        let f_nodes = &self.edges.get(f)?.nodes;
+        let min_size = e_nodes.len().min(f_nodes.len());
+        if min_size == 0 {
+            return Some(0.0);
+        }
+        Some(e_nodes.intersection(f_nodes).len() as f64 / min_size as f64)
+    }
+
+    /// The overlap coefficient of every pair of intersecting edges, keyed by
+    /// the pair of edge IDs with the smaller ID first.
+    pub fn overlap_coefficients(&self) -> HashMap<(EdgeID, EdgeID), f64> {
+        self.intersection_sizes()
+            .into_iter()
+            .map(|((e, f), shared)| {
+                let e_size = self.edges.get(&e).unwrap().nodes.len();
+                let f_size = self.edges.get(&f).unwrap().nodes.len();
+                ((e, f), shared as f64 / e_size.min(f_size) as f64)
+            })
+            .collect()
+    }
+
+    /// A histogram mapping each intersection size `|e ∩ f|` to the number of
+    /// unordered pairs of distinct edges that share exactly that many nodes.
+    /// Disjoint pairs are not counted.
+    pub fn edge_intersection_profile(&self) -> BTreeMap<usize, usize> {
+        let mut profile = BTreeMap::new();
+        for shared in self.intersection_sizes().into_values() {
+            *profile.entry(shared).or_insert(0) += 1;
+        }
+        profile
+    }
+
+    /// The number of shared nodes of each pair of intersecting edges, found
+    /// by counting how many nodes each pair of edges appears together at.
+    fn intersection_sizes(&self) -> FxHashMap<(EdgeID, EdgeID), usize> {
+        let mut sizes = FxHashMap::default();
+        for node in self.nodes.values() {
+            let mut edges: Vec<EdgeID> = node.containing_edges.iter().cloned().collect();
+            edges.sort();
+            for (ix, e) in edges.iter().enumerate() {
+                for f in edges[ix + 1..].iter() {
+                    *sizes.entry((*e, *f)).or_insert(0) += 1;
+                }
+            }
+        }
+        sizes
+    }
+}
+
+/// Whether three distinct edges can be picked, one from each set, using
+/// Hall's condition.
+fn has_distinct_representatives<EdgeID: HgNode>(
+    a: &FxHashSet<EdgeID>,
+    b: &FxHashSet<EdgeID>,
+    c: &FxHashSet<EdgeID>,
+) -> bool {
+    if a.is_empty() || b.is_empty() || c.is_empty() {
+        return false;
+    }
+    let pairs_ok = a.union(b).count() >= 2 && a.union(c).count() >= 2 && b.union(c).count() >= 2;
+    if !pairs_ok {
+        return false;
+    }
+    let mut all: FxHashSet<EdgeID> = a.union(b).cloned().collect();
+    all.extend(c.iter().cloned());
+    all.len() >= 3
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use crate::HGraph;
+
+    #[test]
+    fn estrada_ignores_single_edges() {
+        let mut hg = HGraph::<(), ()>::new();
+        hg.add_nodes(3);
+        hg.add_edge([0, 1, 2], ());
+        assert_eq!(hg.local_estrada_clustering(&0), Some(0.0));
+        assert_eq!(hg.estrada_clustering(), 0.0);
+
+        hg.add_edge([0, 1], ());
+        hg.add_edge([1, 2], ());
+        // Now 0 - [0, 1] - 1 - [1, 2] - 2 - [0, 1, 2] - 0 is a hypertriangle.
+        assert_eq!(hg.local_estrada_clustering(&0), Some(1.0));
+        assert_eq!(hg.estrada_clustering(), 1.0);
+        assert_eq!(hg.local_estrada_clustering(&7), None);
+
+        let mut path = HGraph::<(), ()>::new();
+        path.add_nodes(4);
+        path.add_edge([0, 1], ());
+        path.add_edge([1, 2], ());
+        path.add_edge([1, 3], ());
+        path.add_edge([2, 3], ());
+        assert_eq!(path.local_estrada_clustering(&1), Some(1.0 / 3.0));
+    }
+
+    #[test]
+    fn zhou_and_overlaps() {
+        let mut hg = HGraph::<(), ()>::new();
+        hg.add_nodes(5);
+        let e1 = hg.add_edge([0, 1, 2], ());
+        let e2 = hg.add_edge([0, 1, 3], ());
+        let e3 = hg.add_edge([0, 4], ());
+        // Node 0 has neighbors 1, 2, 3, 4 and overlaps 1, 0, 0 over three pairs.
+        let local = hg.local_zhou_clustering(&0).unwrap();
+        assert!((local - 1.0 / 12.0).abs() < 1e-12);
+        assert_eq!(hg.local_zhou_clustering(&4), Some(0.0));
+
+        assert_eq!(hg.overlap_coefficient(&e1, &e2), Some(2.0 / 3.0));
+        assert_eq!(hg.overlap_coefficient(&e1, &e3), Some(0.5));
+        assert_eq!(hg.overlap_coefficients().len(), 3);
+        assert_eq!(
+            hg.edge_intersection_profile(),
+            BTreeMap::from([(1, 2), (2, 1)])
+        );
+
+        // Leaves node 0 in two edges {0} without any neighbors.
+        let mut lonely = HGraph::<(), ()>::new();
+        lonely.add_nodes(3);
+        lonely.add_edge([0, 1], ());
+        lonely.add_edge([0, 2], ());
+        lonely.remove_node(1);
+        lonely.remove_node(2);
+        assert_eq!(lonely.local_zhou_clustering(&0), Some(0.0));
+    }
+}
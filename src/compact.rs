@@ -0,0 +1,238 @@
+use std::collections::HashMap;
+
+use fxhash::FxHashSet;
+
+use crate::{EdgeSet, GraphEvent, HGraph, HgNode};
+
+impl<NodeData, EdgeData, NodeID: HgNode, EdgeID: HgNode>
+    HGraph<NodeData, EdgeData, NodeID, EdgeID>
+{
+    /// Renumbers the nodes and edges as `0..num_nodes` and `0..num_edges`,
+    /// keeping their relative order, and returns the old to new mappings for
+    /// both. New IDs continue after the compacted ones. Every existing
+    /// [`NodeHandle`](crate::NodeHandle) and [`EdgeHandle`](crate::EdgeHandle)
+    /// becomes stale if anything moved. Panics for ID types that are not
+    /// counters, like `Uuid`.
+    pub fn compact(&mut self) -> (HashMap<NodeID, NodeID>, HashMap<EdgeID, EdgeID>) {
+        if NodeID::zero().dense_index().is_none() || EdgeID::zero().dense_index().is_none() {
+            panic!("Only counter ID types can be compacted.")
+        }
+        let node_map = dense_mapping(self.nodes.keys().cloned().collect());
+        let edge_map = dense_mapping(self.edges.keys().cloned().collect());
+        self.next_node_id = next_after(node_map.values());
+        self.next_edge_id = next_after(edge_map.values());
+        self.node_alloc.clear();
+        self.edge_alloc.clear();
+        self.rebuild(&node_map, &edge_map);
+        (node_map, edge_map)
+    }
+
+    /// Gives the nodes in `mapping` new IDs, nodes that are not mapped keep
+    /// theirs. Edges keep their IDs and data with their nodes updated.
+    /// Returns an error without changing anything if a mapped node is not
+    /// present, if two nodes would end up with the same ID or if a counter
+    /// ID would be the largest value of its type, which is never handed out.
+    /// With [dense storage](HGraph::use_dense_storage) the node storage grows
+    /// up to the largest new ID, so relabeling to far apart IDs costs memory
+    /// in proportion to that ID. Stales every existing handle.
+    pub fn relabel_nodes(&mut self, mapping: &HashMap<NodeID, NodeID>) -> Result<(), String> {
+        if let Some(missing) = mapping.keys().find(|node| !self.nodes.contains_key(node)) {
+            return Err(format!(
+                "Node {:?} is not present in the hypergraph.",
+                missing
+            ));
+        }
+        if let Some(reserved) = mapping
+            .values()
+            .find(|id| id.dense_index().is_some() && **id == NodeID::max_number())
+        {
+            return Err(format!(
+                "Node ID {:?} is reserved and cannot be relabeled to.",
+                reserved
+            ));
+        }
+        let mut taken = FxHashSet::default();
+        for node in self.nodes.keys() {
+            let new_id = mapping.get(node).unwrap_or(node);
+            if !taken.insert(*new_id) {
+                return Err(format!("Two nodes would be relabeled to {:?}.", new_id));
+            }
+        }
+        // Counter IDs have to skip past anything the mapping handed out.
+        if let Some(max) = taken.iter().max() {
+            if max.dense_index().is_some() && *max >= self.next_node_id {
+                self.next_node_id = next_after([*max].iter());
+            }
+        }
+        self.node_alloc.retain_free(|id| !taken.contains(id));
+        for old_id in mapping.keys() {
+            if !taken.contains(old_id) {
+                self.node_alloc.release(*old_id);
+            }
+        }
+        self.rebuild(mapping, &HashMap::new());
+        Ok(())
+    }
+
+    /// Moves every node and edge to its mapped ID, IDs missing from a mapping
+    /// are kept, then rebuilds any enabled indices.
+    fn rebuild(&mut self, node_map: &HashMap<NodeID, NodeID>, edge_map: &HashMap<EdgeID, EdgeID>) {
+        let moved = node_map.iter().any(|(old, new)| old != new)
+            || edge_map.iter().any(|(old, new)| old != new);
+        if !moved {
+            return;
+        }
+        let node_id = |id: NodeID| *node_map.get(&id).unwrap_or(&id);
+        let edge_id = |id: EdgeID| *edge_map.get(&id).unwrap_or(&id);
+
+        let mut nodes = self.nodes.new_like();
+        for (id, mut node) in std::mem::take(&mut self.nodes) {
+            node.containing_edges = node.containing_edges.into_iter().map(edge_id).collect();
+            nodes.insert(node_id(id), node);
+        }
+        let mut edges = self.edges.new_like();
+        for (id, mut edge) in std::mem::take(&mut self.edges) {
+            let edge_nodes: Vec<NodeID> = edge.nodes.0.iter().map(|node| node_id(*node)).collect();
+            edge.nodes = EdgeSet::from(edge_nodes);
+            edges.insert(edge_id(id), edge);
+        }
+        self.nodes = nodes;
+        self.edges = edges;
+
+        if let Some((bands, rows)) = self
+            .similarity
+            .as_ref()
+            .map(|index| (index.bands, index.rows))
+        {
+            self.enable_similarity_index(bands, rows);
+        }
+        if self.inclusion.is_some() {
+            self.enable_inclusion_index();
+        }
+        if self.facets.is_some() {
+            self.facets = None;
+            self.enable_facet_tracking();
+        }
+        self.bump_generation();
+        self.emit(GraphEvent::IdsReassigned);
+    }
+}
+
+/// Maps the sorted IDs onto `0, 1, 2, ...`.
+fn dense_mapping<ID: HgNode>(mut ids: Vec<ID>) -> HashMap<ID, ID> {
+    ids.sort();
+    let mut next = ID::zero();
+    ids.into_iter()
+        .map(|id| {
+            let new_id = next;
+            next.plus_one();
+            (id, new_id)
+        })
+        .collect()
+}
+
+/// One past the largest of the IDs, zero if there are none.
+fn next_after<'a, ID: HgNode + 'a>(ids: impl Iterator<Item = &'a ID>) -> ID {
+    ids.max().map_or(ID::zero(), |max| {
+        let mut next = *max;
+        next.plus_one();
+        next
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use crate::{sorted, ConGraph, HGraph, HyperGraph, IdPolicy};
+
+    #[test]
+    fn compact_after_removals() {
+        let mut hg = HGraph::<char, u8>::new();
+        for c in ['a', 'b', 'c', 'd', 'e', 'f'] {
+            hg.add_node(c);
+        }
+        hg.add_edge([0, 1], 0);
+        hg.add_edge([1, 2, 3], 1);
+        hg.add_edge([3, 5], 2);
+        hg.add_edge([4, 5], 3);
+        hg.enable_inclusion_index();
+        hg.enable_facet_tracking();
+        hg.remove_node(0);
+        hg.remove_node(4);
+        hg.remove_edge(1);
+        let handle = hg.node_handle(5).unwrap();
+
+        let (node_map, edge_map) = hg.compact();
+        assert_eq!(node_map[&5], 3);
+        assert_eq!(edge_map[&3], 2);
+        assert_eq!(sorted(hg.nodes()), vec![0, 1, 2, 3]);
+        assert_eq!(sorted(hg.edges()), vec![0, 1, 2]);
+        assert_eq!(hg.get_node(&3), Some(&'f'));
+        assert_eq!(hg.query_edge(&1), Some(vec![2, 3]));
+        assert_eq!(hg.get_edge(&2), Some(&3));
+        assert_eq!(sorted(hg.incident_edges(&3)), vec![1, 2]);
+        assert_eq!(sorted(hg.containing_edges_of_nodes([3])), vec![1]);
+        assert_eq!(sorted(hg.facets()), vec![0, 1]);
+        assert!(hg.resolve_node(&handle).is_err());
+        assert_eq!(hg.add_node('g'), 4);
+        assert_eq!(hg.add_edge([0, 4], 4), 3);
+
+        let mut cg = ConGraph::new();
+        cg.add_nodes(3);
+        cg.remove_node(1);
+        cg.compact();
+        assert_eq!(cg.nodes(), vec![0, 1]);
+    }
+
+    #[test]
+    fn relabel_permutation() {
+        let mut hg = HGraph::<u8, ()>::new();
+        for x in 0..4 {
+            hg.add_node(x);
+        }
+        let e0 = hg.add_edge([0, 1, 2], ());
+        let e1 = hg.add_edge([2, 3], ());
+        let swap: HashMap<u32, u32> = [(0, 3), (3, 0), (1, 10)].into();
+        hg.relabel_nodes(&swap).unwrap();
+        assert_eq!(hg.get_node(&3), Some(&0));
+        assert_eq!(hg.get_node(&10), Some(&1));
+        assert!(hg.get_node(&1).is_none());
+        assert_eq!(hg.query_edge(&e0), Some(vec![2, 3, 10]));
+        assert_eq!(hg.query_edge(&e1), Some(vec![0, 2]));
+        assert_eq!(hg.find_id([10, 2, 3]), Some(e0));
+        assert_eq!(sorted(hg.incident_edges(&2)), vec![e0, e1]);
+        assert_eq!(hg.add_node(4), 11);
+
+        let clash: HashMap<u32, u32> = [(0, 2)].into();
+        assert!(hg.relabel_nodes(&clash).is_err());
+        let missing: HashMap<u32, u32> = [(1, 5)].into();
+        assert!(hg.relabel_nodes(&missing).is_err());
+        assert_eq!(hg.query_edge(&e1), Some(vec![0, 2]));
+
+        let mut reuse = HGraph::<(), ()>::new();
+        reuse.set_node_id_policy(IdPolicy::ReuseFifo);
+        reuse.add_nodes(2);
+        reuse.relabel_nodes(&[(1, 10)].into()).unwrap();
+        assert_eq!(reuse.add_node(()), 1);
+        assert_eq!(reuse.add_node(()), 11);
+    }
+
+    #[test]
+    fn relabel_limits() {
+        let mut small = HGraph::<(), (), u8, u8>::new();
+        small.add_nodes(2);
+        let edge = small.add_edge([0, 1], ());
+        assert!(small.relabel_nodes(&[(1, 255)].into()).is_err());
+        assert_eq!(small.query_edge(&edge), Some(vec![0, 1]));
+        small.relabel_nodes(&[(1, 254)].into()).unwrap();
+        assert_eq!(small.query_edge(&edge), Some(vec![0, 254]));
+
+        // Dense storage grows up to the new ID and keeps counting after it.
+        let mut cg = ConGraph::<u16, u8>::default();
+        cg.add_nodes(2);
+        cg.relabel_nodes(&[(1, 5000)].into()).unwrap();
+        assert_eq!(cg.nodes(), vec![0, 5000]);
+        assert_eq!(cg.add_node(), 5001);
+    }
+}
@@ -1,48 +1,91 @@
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 use std::fmt::Display;
 use std::fs;
 use std::fs::File;
 use std::io::Write;
 use std::path::Path;
 use std::str::FromStr;
+use std::sync::mpsc::Receiver;
 
 use serde::{Deserialize, Serialize};
 
-use crate::{HGraph, HyperGraph};
+use crate::{CanonicalLabeling, GraphEvent, HGraph, HgNode, HyperGraph, HyperGraphMut, IdPolicy};
 
 use crate::EdgeSet;
 
-type EdgeID = u64;
+/// The default node ID type of a [`ConGraph`].
+#[cfg(not(feature = "u128_id"))]
+pub type DefaultNodeID = u32;
+/// The default node ID type of a [`ConGraph`], set by **"u128_id"**.
+#[cfg(feature = "u128_id")]
+pub type DefaultNodeID = u128;
+
+/// The default edge ID type of a [`ConGraph`].
+#[cfg(not(any(feature = "u128_id", feature = "u32_edge_id")))]
+pub type DefaultEdgeID = u64;
+/// The default edge ID type of a [`ConGraph`], set by **"u128_id"**.
+#[cfg(all(feature = "u128_id", not(feature = "u32_edge_id")))]
+pub type DefaultEdgeID = u128;
+/// The default edge ID type of a [`ConGraph`], set by **"u32_edge_id"**.
+#[cfg(feature = "u32_edge_id")]
+pub type DefaultEdgeID = u32;
 
 /// A connectivity only hypergraph object. Essentially a wrapper
 /// around `HGraph` with simpler add nodes/edges and simpler
-/// serialization to and from disk.
+/// serialization to and from disk. Generic over the unsigned integer types
+/// used for node and edge IDs, which default to [`DefaultNodeID`] and
+/// [`DefaultEdgeID`].
 #[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct ConGraph {
-    pub(crate) core: HGraph<(), ()>,
+#[serde(from = "SerdeConGraph<NodeID, EdgeID>")]
+pub struct ConGraph<NodeID: HgNode = DefaultNodeID, EdgeID: HgNode = DefaultEdgeID> {
+    pub(crate) core: HGraph<(), (), NodeID, EdgeID>,
 }
 
-impl Default for ConGraph {
+/// The serialized form of a `ConGraph`, which does not record the storage
+/// mode of `core`, so it is switched back to dense storage after reading.
+#[derive(Deserialize)]
+struct SerdeConGraph<NodeID: HgNode, EdgeID: HgNode> {
+    core: HGraph<(), (), NodeID, EdgeID>,
+}
+
+impl<NodeID: HgNode, EdgeID: HgNode> From<SerdeConGraph<NodeID, EdgeID>>
+    for ConGraph<NodeID, EdgeID>
+{
+    fn from(value: SerdeConGraph<NodeID, EdgeID>) -> Self {
+        let mut core = value.core;
+        core.use_dense_storage();
+        ConGraph { core }
+    }
+}
+
+/// Creates an empty `ConGraph` for any ID types, such as
+/// `ConGraph::<u16, u32>::default()`.
+impl<NodeID: HgNode, EdgeID: HgNode> Default for ConGraph<NodeID, EdgeID> {
     fn default() -> Self {
-        Self::new()
+        let mut core = HGraph::new();
+        core.use_dense_storage();
+        ConGraph { core }
     }
 }
 
 impl ConGraph {
+    /// Creates an empty `ConGraph` with the default ID types, use
+    /// [`default`](ConGraph::default) for others. Nodes and edges are kept in
+    /// dense storage, see [`HGraph::use_dense_storage`].
     pub fn new() -> ConGraph {
-        ConGraph {
-            core: HGraph::new(),
-        }
+        Self::default()
     }
+}
 
+impl<NodeID: HgNode, EdgeID: HgNode> ConGraph<NodeID, EdgeID> {
     /// Panics if new node cannot be added.
-    pub fn add_node(&mut self) -> u32 {
+    pub fn add_node(&mut self) -> NodeID {
         self.core.add_node(())
     }
 
     /// Adds `num_nodes` nodes to the graph, returning a vector containing
     /// the nodes created. `panic`s if it runs out of nodes to allocate.
-    pub fn add_nodes(&mut self, num_nodes: usize) -> Vec<u32> {
+    pub fn add_nodes(&mut self, num_nodes: usize) -> Vec<NodeID> {
         (0..num_nodes).map(|_| self.core.add_node(())).collect()
     }
 
@@ -51,19 +94,19 @@ impl ConGraph {
     /// retain the edge {1} so that way further nodes can
     /// be added back to the edge. If 1 is then removed the empty edge will be
     /// deleted.
-    pub fn remove_node(&mut self, node: u32) {
+    pub fn remove_node(&mut self, node: NodeID) {
         self.core.remove_node(node);
     }
 
     /// Removes a collection of nodes and any resulting empty edges.
-    pub fn remove_nodes(&mut self, nodes: Vec<u32>) {
+    pub fn remove_nodes(&mut self, nodes: Vec<NodeID>) {
         for node in nodes {
             self.core.remove_node(node);
         }
     }
 
     /// All node IDs that are currently in use.
-    pub fn nodes(&self) -> Vec<u32> {
+    pub fn nodes(&self) -> Vec<NodeID> {
         self.core.nodes.keys().cloned().collect()
     }
 
@@ -72,7 +115,7 @@ impl ConGraph {
     /// ### `panic`s
     /// - If not all nodes are present in the hypergraph
     /// - If you run out of possible id's usable with the `EdgeID` storage type
-    pub fn add_edge(&mut self, nodes: impl AsRef<[u32]>) -> EdgeID {
+    pub fn add_edge(&mut self, nodes: impl AsRef<[NodeID]>) -> EdgeID {
         self.core.add_edge(nodes, ())
     }
 
@@ -83,7 +126,7 @@ impl ConGraph {
     /// In case you forget it :)
     pub fn find_id<E>(&self, nodes: E) -> Option<EdgeID>
     where
-        E: AsRef<[u32]>,
+        E: AsRef<[NodeID]>,
     {
         self.core.find_id(nodes)
     }
@@ -99,8 +142,8 @@ impl ConGraph {
     /// would an edge without any nodes in `cut_nodes`.
     /// The type `ToSet` is any collection that can be converted to a sparse
     /// set representation.
-    pub fn cut(&self, cut_nodes: impl AsRef<[u32]>) -> usize {
-        let cut_as_edge: EdgeSet<u32> = cut_nodes.into();
+    pub fn cut(&self, cut_nodes: impl AsRef<[NodeID]>) -> usize {
+        let cut_as_edge: EdgeSet<NodeID> = cut_nodes.into();
         let mut counted_edges: HashSet<EdgeID> = HashSet::new();
         for node in cut_as_edge.0.iter() {
             let out_edges: Vec<EdgeID> = self
@@ -129,23 +172,166 @@ impl ConGraph {
         file.write_all(s.as_bytes()).expect("Cannot write");
     }
 
+    /// See [`HGraph::canonical_labeling`].
+    pub fn canonical_labeling(&self) -> CanonicalLabeling<NodeID> {
+        self.core.canonical_labeling()
+    }
+
+    /// See [`HGraph::canonical_form`].
+    pub fn canonical_form(&self) -> ConGraph {
+        self.core.canonical_form()
+    }
+
+    /// See [`HGraph::wl_hash`].
+    pub fn wl_hash(&self, iterations: usize) -> u64 {
+        self.core.wl_hash(iterations)
+    }
+
+    /// See [`HGraph::enable_similarity_index`].
+    pub fn enable_similarity_index(&mut self, bands: usize, rows: usize) {
+        self.core.enable_similarity_index(bands, rows)
+    }
+
+    /// See [`HGraph::facets`].
+    pub fn facets(&self) -> Vec<EdgeID> {
+        self.core.facets()
+    }
+
+    /// See [`HGraph::to_simple`].
+    pub fn to_simple(&self) -> ConGraph<NodeID, EdgeID> {
+        ConGraph {
+            core: self.core.to_simple(),
+        }
+    }
+
+    /// See [`HGraph::set_node_id_policy`].
+    pub fn set_node_id_policy(&mut self, policy: IdPolicy) {
+        self.core.set_node_id_policy(policy)
+    }
+
+    /// See [`HGraph::set_edge_id_policy`].
+    pub fn set_edge_id_policy(&mut self, policy: IdPolicy) {
+        self.core.set_edge_id_policy(policy)
+    }
+
+    /// See [`HGraph::compact`].
+    pub fn compact(&mut self) -> (HashMap<NodeID, NodeID>, HashMap<EdgeID, EdgeID>) {
+        self.core.compact()
+    }
+
+    /// See [`HGraph::relabel_nodes`].
+    pub fn relabel_nodes(&mut self, mapping: &HashMap<NodeID, NodeID>) -> Result<(), String> {
+        self.core.relabel_nodes(mapping)
+    }
+
+    /// See [`HGraph::subscribe`].
+    pub fn subscribe(
+        &mut self,
+        callback: impl FnMut(&GraphEvent<NodeID, EdgeID>) + Send + Sync + 'static,
+    ) -> usize {
+        self.core.subscribe(callback)
+    }
+
+    /// See [`HGraph::unsubscribe`].
+    pub fn unsubscribe(&mut self, subscriber: usize) -> bool {
+        self.core.unsubscribe(subscriber)
+    }
+
+    /// See [`HGraph::event_channel`].
+    pub fn event_channel(&mut self) -> Receiver<GraphEvent<NodeID, EdgeID>> {
+        self.core.event_channel()
+    }
+
+    /// See [`HGraph::enable_facet_tracking`].
+    pub fn enable_facet_tracking(&mut self) {
+        self.core.enable_facet_tracking()
+    }
+
+    /// See [`HGraph::enable_inclusion_index`].
+    pub fn enable_inclusion_index(&mut self) {
+        self.core.enable_inclusion_index()
+    }
+
+    /// See [`HGraph::similar_edges`].
+    pub fn similar_edges(
+        &self,
+        nodes: impl AsRef<[NodeID]>,
+        jaccard_threshold: f64,
+    ) -> Vec<(EdgeID, f64)> {
+        self.core.similar_edges(nodes, jaccard_threshold)
+    }
+
+    /// See [`HGraph::top_k_similar_edges`].
+    pub fn top_k_similar_edges(&self, edge_id: &EdgeID, k: usize) -> Vec<(EdgeID, f64)> {
+        self.core.top_k_similar_edges(edge_id, k)
+    }
+
+    /// See [`HGraph::par_edges_of_size`].
+    #[cfg(feature = "rayon")]
+    pub fn par_edges_of_size(&self, card: usize) -> Vec<EdgeID>
+    where
+        NodeID: Send + Sync,
+        EdgeID: Send + Sync,
+    {
+        self.core.par_edges_of_size(card)
+    }
+
+    /// See [`HGraph::par_skeleton`].
+    #[cfg(feature = "rayon")]
+    pub fn par_skeleton(&self, cardinality: usize) -> Vec<EdgeID>
+    where
+        NodeID: Send + Sync,
+        EdgeID: Send + Sync,
+    {
+        self.core.par_skeleton(cardinality)
+    }
+
+    /// See [`HGraph::par_links`].
+    #[cfg(feature = "rayon")]
+    pub fn par_links(&self) -> HashMap<EdgeID, Vec<(EdgeID, Vec<NodeID>)>>
+    where
+        NodeID: Send + Sync,
+        EdgeID: Send + Sync,
+    {
+        self.core.par_links()
+    }
+
     pub fn from_file(path: &Path) -> Option<Self> {
         if path.is_file() == false {
             return None;
         }
         if let Ok(hg_string) = fs::read_to_string(path) {
-            if let Ok(serde_out) = ConGraph::from_str(&hg_string) {
-                Some(serde_out)
-            } else {
-                None
-            }
+            Self::from_str(&hg_string).ok()
         } else {
             None
         }
     }
 }
 
-impl Display for ConGraph {
+/// Two `ConGraph`s are equal if they have the same node IDs and the same
+/// edge IDs mapping to the same nodes.
+impl<NodeID: HgNode, EdgeID: HgNode> PartialEq for ConGraph<NodeID, EdgeID> {
+    fn eq(&self, other: &Self) -> bool {
+        self.core.nodes.len() == other.core.nodes.len()
+            && self.core.edges.len() == other.core.edges.len()
+            && self
+                .core
+                .nodes
+                .keys()
+                .all(|node| other.core.nodes.contains_key(node))
+            && self.core.edges.iter().all(|(id, edge)| {
+                other
+                    .core
+                    .edges
+                    .get(id)
+                    .is_some_and(|other_edge| other_edge.nodes == edge.nodes)
+            })
+    }
+}
+
+impl<NodeID: HgNode, EdgeID: HgNode> Eq for ConGraph<NodeID, EdgeID> {}
+
+impl<NodeID: HgNode, EdgeID: HgNode> Display for ConGraph<NodeID, EdgeID> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         if self.core.nodes.len() == 0 {
             println!("Graph is empty. Add nodes for more fun.");
@@ -169,15 +355,43 @@ impl Display for ConGraph {
     }
 }
 
-impl HyperGraph for ConGraph {
-    type NodeID = u32;
+impl<NodeID: HgNode, EdgeID: HgNode> HyperGraph for ConGraph<NodeID, EdgeID> {
+    type NodeID = NodeID;
 
-    type EdgeID = u64;
+    type EdgeID = EdgeID;
 
     fn query_edge(&self, edge: &Self::EdgeID) -> Option<Vec<Self::NodeID>> {
         self.core.query_edge(edge)
     }
 
+    fn node_ids(&self) -> impl Iterator<Item = Self::NodeID> + '_ {
+        self.core.node_ids()
+    }
+
+    fn edge_ids(&self) -> impl Iterator<Item = Self::EdgeID> + '_ {
+        self.core.edge_ids()
+    }
+
+    fn num_nodes(&self) -> usize {
+        self.core.num_nodes()
+    }
+
+    fn num_edges(&self) -> usize {
+        self.core.num_edges()
+    }
+
+    fn degree(&self, node: &Self::NodeID) -> Option<usize> {
+        self.core.degree(node)
+    }
+
+    fn edge_size(&self, edge: &Self::EdgeID) -> Option<usize> {
+        self.core.edge_size(edge)
+    }
+
+    fn incident_edges(&self, node: &Self::NodeID) -> Vec<Self::EdgeID> {
+        self.core.incident_edges(node)
+    }
+
     fn containing_edges_of_nodes(&self, nodes: impl AsRef<[Self::NodeID]>) -> Vec<Self::EdgeID> {
         self.core.containing_edges_of_nodes(nodes)
     }
@@ -228,25 +442,158 @@ impl HyperGraph for ConGraph {
     fn boundary_down_of_nodes(&self, nodes: impl AsRef<[Self::NodeID]>) -> Vec<Vec<Self::NodeID>> {
         self.core.boundary_down_of_nodes(nodes)
     }
+
+    fn containing_edges_iter(
+        &self,
+        edge: &Self::EdgeID,
+    ) -> impl Iterator<Item = Self::EdgeID> + '_ {
+        self.core.containing_edges_iter(edge)
+    }
+
+    fn containing_edges_of_nodes_iter(
+        &self,
+        nodes: impl AsRef<[Self::NodeID]>,
+    ) -> impl Iterator<Item = Self::EdgeID> + '_ {
+        self.core.containing_edges_of_nodes_iter(nodes)
+    }
+
+    fn link_iter(
+        &self,
+        edge: &Self::EdgeID,
+    ) -> impl Iterator<Item = (Self::EdgeID, Vec<Self::NodeID>)> + '_ {
+        self.core.link_iter(edge)
+    }
+
+    fn link_of_nodes_iter(
+        &self,
+        nodes: impl AsRef<[Self::NodeID]>,
+    ) -> impl Iterator<Item = (Self::EdgeID, Vec<Self::NodeID>)> + '_ {
+        self.core.link_of_nodes_iter(nodes)
+    }
+
+    fn maximal_edges_iter(
+        &self,
+        edge_id: &Self::EdgeID,
+    ) -> impl Iterator<Item = Self::EdgeID> + '_ {
+        self.core.maximal_edges_iter(edge_id)
+    }
+
+    fn maximal_edges_of_nodes_iter(
+        &self,
+        nodes: impl AsRef<[Self::NodeID]>,
+    ) -> impl Iterator<Item = Self::EdgeID> + '_ {
+        self.core.maximal_edges_of_nodes_iter(nodes)
+    }
+
+    fn edges_of_size_iter(&self, card: usize) -> impl Iterator<Item = Self::EdgeID> + '_ {
+        self.core.edges_of_size_iter(card)
+    }
+
+    fn skeleton_iter(&self, cardinality: usize) -> impl Iterator<Item = Self::EdgeID> + '_ {
+        self.core.skeleton_iter(cardinality)
+    }
+
+    fn boundary_up_ids_iter(
+        &self,
+        edge_id: &Self::EdgeID,
+    ) -> impl Iterator<Item = Self::EdgeID> + '_ {
+        self.core.boundary_up_ids_iter(edge_id)
+    }
+
+    fn boundary_down_ids_iter(
+        &self,
+        edge_id: &Self::EdgeID,
+    ) -> impl Iterator<Item = Self::EdgeID> + '_ {
+        self.core.boundary_down_ids_iter(edge_id)
+    }
+
+    fn boundary_up_of_nodes_ids_iter(
+        &self,
+        nodes: impl AsRef<[Self::NodeID]>,
+    ) -> impl Iterator<Item = Self::EdgeID> + '_ {
+        self.core.boundary_up_of_nodes_ids_iter(nodes)
+    }
+
+    fn boundary_down_of_nodes_ids_iter(
+        &self,
+        nodes: impl AsRef<[Self::NodeID]>,
+    ) -> impl Iterator<Item = Self::EdgeID> + '_ {
+        self.core.boundary_down_of_nodes_ids_iter(nodes)
+    }
+
+    fn faces(&self, edge_id: &Self::EdgeID) -> Vec<Self::EdgeID> {
+        self.core.faces(edge_id)
+    }
+
+    fn faces_of_nodes(&self, nodes: impl AsRef<[Self::NodeID]>) -> Vec<Self::EdgeID> {
+        self.core.faces_of_nodes(nodes)
+    }
+
+    fn cofaces(&self, edge_id: &Self::EdgeID) -> Vec<Self::EdgeID> {
+        self.core.cofaces(edge_id)
+    }
+
+    fn open_star(&self, edge_id: &Self::EdgeID) -> Vec<Self::EdgeID> {
+        self.core.open_star(edge_id)
+    }
+
+    fn closed_star(&self, edge_id: &Self::EdgeID) -> Vec<Self::EdgeID> {
+        self.core.closed_star(edge_id)
+    }
+
+    fn deletion(&self, edge_id: &Self::EdgeID) -> Vec<Self::EdgeID> {
+        self.core.deletion(edge_id)
+    }
+}
+
+impl<NodeID: HgNode, EdgeID: HgNode> HyperGraphMut for ConGraph<NodeID, EdgeID> {
+    type NodeData = ();
+    type EdgeData = ();
+
+    fn add_node_default(&mut self) -> Self::NodeID {
+        self.add_node()
+    }
+
+    fn add_edge_default(&mut self, nodes: impl AsRef<[Self::NodeID]>) -> Self::EdgeID {
+        self.core.add_edge_default(nodes)
+    }
+
+    fn remove_node(&mut self, node: Self::NodeID) -> Option<Self::NodeData> {
+        self.core.remove_node(node)
+    }
+
+    fn remove_edge(&mut self, edge: Self::EdgeID) -> Option<Self::EdgeData> {
+        self.core.remove_edge(edge)
+    }
+
+    fn contract(&mut self, nodes: impl AsRef<[Self::NodeID]>) -> Option<Self::NodeID> {
+        self.core.contract(nodes)
+    }
 }
 
-impl FromStr for ConGraph {
+/// Parses the format written by `Display`, a line `nodes:` followed by the
+/// node IDs and a line `edges:` followed by one edge per line. Node IDs are
+/// kept and edges get new IDs in the order they are listed.
+impl<NodeID: HgNode, EdgeID: HgNode> FromStr for ConGraph<NodeID, EdgeID> {
     type Err = String;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         // TODO: Convert this to regex
         let lines: Vec<&str> = s.lines().collect();
-        let mut nodes_ix = 0;
+        let mut nodes_ix = None;
         let mut edges_start_ix = 0;
         for ix in 0..lines.len() {
             if lines[ix] == "nodes:" {
-                nodes_ix = ix + 1;
+                nodes_ix = Some(ix + 1);
             }
             if lines[ix] == "edges:" {
                 edges_start_ix = ix + 1;
             }
         }
-        let mut node_string = lines[nodes_ix].to_string();
+        let mut node_string = nodes_ix
+            .and_then(|ix| lines.get(ix))
+            .ok_or_else(|| "Missing the list of nodes after \"nodes:\".".to_string())?
+            .to_string();
         if node_string.starts_with('[') {
             node_string.remove(0);
         }
@@ -255,7 +602,11 @@ impl FromStr for ConGraph {
         }
         let mut nodes = HashSet::new();
         for node in node_string.split(',') {
-            nodes.insert(node.trim().parse::<u32>().expect("node parse error."));
+            let node = node.trim();
+            nodes.insert(
+                node.parse::<NodeID>()
+                    .map_err(|_| format!("Could not parse node ID {:?}.", node))?,
+            );
         }
         let mut edges = Vec::new();
         for edge_ix in edges_start_ix..lines.len() {
@@ -268,20 +619,38 @@ impl FromStr for ConGraph {
             }
             let mut node_set = Vec::new();
             for node_str in edge_string.split(',') {
-                println!("node_str: {:}", node_str);
-                node_set.push(node_str.trim().parse::<u32>().expect("node parse error."));
+                let node_str = node_str.trim();
+                node_set.push(
+                    node_str
+                        .parse::<NodeID>()
+                        .map_err(|_| format!("Could not parse node ID {:?}.", node_str))?,
+                );
             }
             edges.push(node_set);
         }
-        let max_seen_node = nodes.iter().fold(0_u32, |acc, e| acc.max(*e)) + 1;
-        let mut core = HGraph::<(), (), u32, u64>::new();
-        let _: Vec<_> = (0..=max_seen_node).map(|_| core.add_node(())).collect();
-        for ix in 0..=max_seen_node {
-            if nodes.contains(&ix) == false {
-                core.remove_node(ix);
-            }
+        // The largest ID marks the counter as exhausted and is never handed out.
+        if nodes.contains(&NodeID::max_number()) {
+            return Err(format!(
+                "Node ID {:?} is out of range, the largest ID is reserved.",
+                NodeID::max_number()
+            ));
+        }
+        let mut core = HGraph::<(), (), NodeID, EdgeID>::new();
+        core.use_dense_storage();
+        for node in nodes.iter() {
+            core.add_node_with_id((), *node);
+        }
+        if let Some(max) = nodes.iter().max() {
+            core.next_node_id = *max;
+            core.next_node_id.plus_one();
         }
         for edge in edges.into_iter() {
+            if let Some(missing) = edge.iter().find(|node| !nodes.contains(node)) {
+                return Err(format!(
+                    "Edge node {:?} is not in the list of nodes.",
+                    missing
+                ));
+            }
             core.add_edge(edge, ());
         }
         Ok(ConGraph { core })
@@ -293,17 +662,17 @@ mod test {
 
     use std::str::FromStr;
 
-    use crate::{congraph::ConGraph, HyperGraph};
+    use crate::{congraph::ConGraph, DefaultNodeID, HGraph, HyperGraph};
 
     #[test]
     fn test_creating_and_deleting_nodes() {
         let mut hg = ConGraph::new();
         let first_100 = hg.add_nodes(100);
-        assert_eq!(first_100, (0_u32..100_u32).collect::<Vec<u32>>());
-        let removed = 99_u32;
+        assert_eq!(first_100, (0..100).collect::<Vec<DefaultNodeID>>());
+        let removed = 99;
         hg.remove_node(removed);
         let one_hundred = hg.add_nodes(1);
-        assert_eq!(one_hundred[0], 100_u32);
+        assert_eq!(one_hundred[0], 100);
     }
 
     #[test]
@@ -327,7 +696,7 @@ mod test {
         hg.add_edge(&[0, 1, 3, 5]);
         println!("hg:\n{:}", hg);
         let s = hg.to_string();
-        let hg_parsed = ConGraph::from_str(&s).expect("no parsing?");
+        let hg_parsed: ConGraph = ConGraph::from_str(&s).expect("no parsing?");
         println!("hg_parsed:\n{:}", hg_parsed);
         dbg!(&hg.core);
         let s3 = serde_json::to_string(&hg).expect("could not serialize next_usable_node");
@@ -335,6 +704,30 @@ mod test {
         dbg!(s3);
     }
 
+    #[test]
+    fn generic_id_types() {
+        let mut cg = ConGraph::<u16, u8>::default();
+        let nodes = cg.add_nodes(5);
+        let e0 = cg.add_edge(&nodes[..3]);
+        cg.add_edge([nodes[3], nodes[4]]);
+        cg.remove_node(nodes[1]);
+        assert!(cg.core.has_dense_storage());
+        assert_eq!(cg.query_edge(&e0), Some(vec![0_u16, 2]));
+
+        let parsed = ConGraph::<u16, u8>::from_str(&cg.to_string()).unwrap();
+        assert_eq!(parsed.nodes().len(), 4);
+        assert!(parsed.find_id([0, 2]).is_some());
+        assert!(ConGraph::<u16, u8>::from_str("nodes:\n[0, 70000]\nedges:\n").is_err());
+        assert!(ConGraph::<u16, u8>::from_str("nodes:\n[0, 1]\nedges:\n[0, 5]\n").is_err());
+        assert!(ConGraph::<u8, u8>::from_str("nodes:\n[0, 255]\nedges:\n[0, 255]").is_err());
+        assert!(ConGraph::<u16, u8>::from_str("").is_err());
+        assert!(ConGraph::<u16, u8>::from_str("nodes:").is_err());
+
+        let hg = HGraph::from_congraph(cg, |node| *node as u32, |_| ());
+        assert_eq!(hg.get_node(&4), Some(&4_u32));
+        assert_eq!(hg.find_id([3, 4]), Some(1_u8));
+    }
+
     #[test]
     fn link_and_skeleton() {
         let mut hg = ConGraph::new();
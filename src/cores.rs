@@ -0,0 +1,238 @@
+use std::collections::{BTreeSet, HashMap};
+
+use fxhash::{FxHashMap, FxHashSet};
+
+use crate::{EdgeSet, HGraph, HgNode};
+
+/// The coreness of every node of a hypergraph, computed by
+/// [`HGraph::core_decomposition`] or [`HGraph::kt_core_decomposition`]. A
+/// node with coreness `k` belongs to the `k`-core but not the `k + 1`-core.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CoreDecomposition<NodeID: HgNode> {
+    pub coreness: HashMap<NodeID, usize>,
+    /// The `t` of a (k, t)-core decomposition, `None` if edges had to be
+    /// fully contained in the core.
+    pub t: Option<usize>,
+}
+
+impl<NodeID: HgNode> CoreDecomposition<NodeID> {
+    /// The nodes with coreness at least `k`.
+    pub fn core_nodes(&self, k: usize) -> Vec<NodeID> {
+        self.coreness
+            .iter()
+            .filter(|(_, coreness)| **coreness >= k)
+            .map(|(node, _)| *node)
+            .collect()
+    }
+
+    /// The largest `k` with a non-empty `k`-core.
+    pub fn max_coreness(&self) -> usize {
+        self.coreness.values().cloned().max().unwrap_or(0)
+    }
+}
+
+impl<NodeData, EdgeData, NodeID: HgNode, EdgeID: HgNode>
+    HGraph<NodeData, EdgeData, NodeID, EdgeID>
+{
+    /// Computes the coreness of each node, where the `k`-core is the largest
+    /// set of nodes such that every node is in at least `k` edges of the
+    /// induced subhypergraph, meaning only edges fully contained in the set
+    /// count.
+    pub fn core_decomposition(&self) -> CoreDecomposition<NodeID> {
+        CoreDecomposition {
+            coreness: self.peel(None),
+            t: None,
+        }
+    }
+
+    /// Computes the coreness of each node for the (k, t)-core, the largest
+    /// set of nodes such that every node is in at least `k` edges that keep
+    /// at least `t` of their nodes within the set. Edges with fewer than `t`
+    /// nodes never count.
+    pub fn kt_core_decomposition(&self, t: usize) -> CoreDecomposition<NodeID> {
+        CoreDecomposition {
+            coreness: self.peel(Some(t.max(1))),
+            t: Some(t.max(1)),
+        }
+    }
+
+    /// Repeatedly removes a node of minimum degree, the coreness of a node
+    /// is the largest minimum degree seen up to its removal. An edge stops
+    /// counting towards degrees once it has fewer than `t` nodes left, or
+    /// once it loses any node if `t` is `None`.
+    fn peel(&self, t: Option<usize>) -> HashMap<NodeID, usize> {
+        let mut edge_ids: Vec<EdgeID> = self.edges.keys().cloned().collect();
+        edge_ids.sort();
+        let edge_position: FxHashMap<EdgeID, usize> = edge_ids
+            .iter()
+            .enumerate()
+            .map(|(ix, id)| (*id, ix))
+            .collect();
+        let edge_sizes: Vec<usize> = edge_ids
+            .iter()
+            .map(|id| self.edges.get(id).unwrap().nodes.len())
+            .collect();
+        let survives = |size: usize, remaining: usize| match t {
+            Some(t) => remaining >= t,
+            None => remaining == size,
+        };
+        let mut remaining = edge_sizes.clone();
+        let mut edge_alive: Vec<bool> = edge_sizes
+            .iter()
+            .map(|size| survives(*size, *size))
+            .collect();
+
+        let mut degree: FxHashMap<NodeID, usize> = self
+            .nodes
+            .iter()
+            .map(|(id, node)| {
+                let alive = node
+                    .containing_edges
+                    .iter()
+                    .filter(|edge_id| edge_alive[edge_position[*edge_id]])
+                    .count();
+                (*id, alive)
+            })
+            .collect();
+        let mut queue: BTreeSet<(usize, NodeID)> =
+            degree.iter().map(|(id, deg)| (*deg, *id)).collect();
+        let mut coreness = HashMap::with_capacity(degree.len());
+        let mut k = 0;
+        while let Some((node_degree, node)) = queue.pop_first() {
+            k = k.max(node_degree);
+            coreness.insert(node, k);
+            degree.remove(&node);
+            for edge_id in self.nodes.get(&node).unwrap().containing_edges.iter() {
+                let ix = edge_position[edge_id];
+                remaining[ix] -= 1;
+                if !edge_alive[ix] || survives(edge_sizes[ix], remaining[ix]) {
+                    continue;
+                }
+                edge_alive[ix] = false;
+                for other in self.edges.get(edge_id).unwrap().nodes.0.iter() {
+                    if let Some(other_degree) = degree.get_mut(other) {
+                        queue.remove(&(*other_degree, *other));
+                        *other_degree -= 1;
+                        queue.insert((*other_degree, *other));
+                    }
+                }
+            }
+        }
+        coreness
+    }
+}
+
+impl<NodeData, EdgeData, NodeID, EdgeID> HGraph<NodeData, EdgeData, NodeID, EdgeID>
+where
+    NodeID: HgNode,
+    EdgeID: HgNode,
+    NodeData: Clone,
+    EdgeData: Clone,
+{
+    /// Returns the `k`-core of [`core_decomposition`](HGraph::core_decomposition)
+    /// as a new `HGraph` containing the edges fully within the core.
+    pub fn k_core(&self, k: usize) -> HGraph<NodeData, EdgeData, NodeID, EdgeID> {
+        let core: FxHashSet<NodeID> = self
+            .core_decomposition()
+            .core_nodes(k)
+            .into_iter()
+            .collect();
+        self.filter_by_edge(|edge_id| {
+            self.edges
+                .get(&edge_id)
+                .unwrap()
+                .nodes
+                .0
+                .iter()
+                .all(|node| core.contains(node))
+        })
+    }
+
+    /// Returns the (k, t)-core of [`kt_core_decomposition`](HGraph::kt_core_decomposition)
+    /// as a new `HGraph`. Edges keeping at least `t` nodes in the core are
+    /// restricted to the core, if two edges become identical only the one
+    /// with the smaller ID is kept.
+    pub fn kt_core(&self, k: usize, t: usize) -> HGraph<NodeData, EdgeData, NodeID, EdgeID> {
+        let t = t.max(1);
+        let core: FxHashSet<NodeID> = self
+            .kt_core_decomposition(t)
+            .core_nodes(k)
+            .into_iter()
+            .collect();
+        let mut kt_core = self.filter_by_edge(|edge_id| {
+            let nodes = &self.edges.get(&edge_id).unwrap().nodes;
+            nodes.0.iter().filter(|node| core.contains(node)).count() >= t
+        });
+        for node in kt_core.nodes() {
+            if !core.contains(&node) {
+                kt_core.remove_node(node);
+            }
+        }
+        let mut edge_ids = kt_core.edges();
+        edge_ids.sort();
+        let mut seen: FxHashSet<EdgeSet<NodeID>> = FxHashSet::default();
+        for edge_id in edge_ids {
+            let nodes = kt_core.edges.get(&edge_id).unwrap().nodes.clone();
+            if !seen.insert(nodes) {
+                kt_core.remove_edge(edge_id);
+            }
+        }
+        kt_core
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::HGraph;
+
+    #[test]
+    fn induced_cores() {
+        let mut hg = HGraph::<(), ()>::new();
+        hg.add_nodes(6);
+        // 0, 1, 2 are each in three edges among themselves.
+        hg.add_edge([0, 1], ());
+        hg.add_edge([1, 2], ());
+        hg.add_edge([0, 2], ());
+        hg.add_edge([0, 1, 2], ());
+        hg.add_edge([2, 3], ());
+        hg.add_edge([3, 4, 5], ());
+        let cores = hg.core_decomposition();
+        assert_eq!(cores.max_coreness(), 3);
+        let mut core_nodes = cores.core_nodes(3);
+        core_nodes.sort();
+        assert_eq!(core_nodes, vec![0, 1, 2]);
+        assert_eq!(cores.coreness[&3], 1);
+        assert_eq!(cores.coreness[&5], 1);
+
+        let core = hg.k_core(3);
+        assert_eq!(core.num_nodes(), 3);
+        assert_eq!(core.num_edges(), 4);
+        assert!(hg.k_core(4).num_edges() == 0);
+    }
+
+    #[test]
+    fn kt_cores() {
+        let mut hg = HGraph::<(), ()>::new();
+        hg.add_nodes(5);
+        hg.add_edge([0, 1, 4], ());
+        hg.add_edge([0, 1, 2], ());
+        hg.add_edge([1, 2, 3], ());
+        hg.add_edge([0, 2, 3], ());
+        // Induced, node 4 only has one edge and taking it out breaks [0, 1, 4].
+        let induced = hg.core_decomposition();
+        assert_eq!(induced.coreness[&4], 1);
+        assert_eq!(induced.coreness[&0], 2);
+
+        // With t = 2 the edge [0, 1, 4] still counts for 0 and 1.
+        let relaxed = hg.kt_core_decomposition(2);
+        assert_eq!(relaxed.coreness[&0], 3);
+        assert_eq!(relaxed.coreness[&1], 3);
+        assert_eq!(relaxed.coreness[&3], 2);
+        let core = hg.kt_core(3, 2);
+        let mut nodes = core.nodes();
+        nodes.sort();
+        assert_eq!(nodes, vec![0, 1, 2]);
+        assert!(core.find_id([0, 1]).is_some());
+        assert_eq!(core.num_edges(), 4);
+    }
+}
@@ -1,23 +1,60 @@
 use std::{
     collections::HashSet,
     fmt::{Debug, Display},
+    marker::PhantomData,
 };
 
 use serde::{Deserialize, Serialize};
+use smallvec::SmallVec;
 
 use crate::HgNode;
 
+/// The number of nodes an [`EdgeSet`] stores inline before moving them to
+/// the heap.
+pub const INLINE_NODES: usize = 4;
+
+/// The node storage of an [`EdgeSet`], edges with at most
+/// [`INLINE_NODES`] nodes do not allocate.
+pub type EdgeNodes<N> = SmallVec<[N; INLINE_NODES]>;
+
 /// A subset for an overall set system, note that for things like
 /// deserializing and using `From`'s we default always to `Undirected`, so
 /// if you want to make a `Edge::Simplex` from a `Vec` you have to do something like
 #[derive(Debug, Clone, Hash, PartialEq, PartialOrd, Ord, Eq)]
-pub struct EdgeSet<N: HgNode>(pub Vec<N>);
+pub struct EdgeSet<N: HgNode>(pub EdgeNodes<N>);
+
+/// Walks two sorted slices at once, pushing the nodes picked by `keep` given
+/// whether each node is in the left side, the right side or both.
+fn merge<N: HgNode>(left: &[N], right: &[N], keep: impl Fn(bool, bool) -> bool) -> EdgeNodes<N> {
+    let mut ret = EdgeNodes::new();
+    let mut left_ix = 0;
+    let mut right_ix = 0;
+    while left_ix < left.len() || right_ix < right.len() {
+        let (node, in_left, in_right) = match (left.get(left_ix), right.get(right_ix)) {
+            (Some(l), Some(r)) if l == r => (*l, true, true),
+            (Some(l), Some(r)) if l < r => (*l, true, false),
+            (Some(l), None) => (*l, true, false),
+            (_, Some(r)) => (*r, false, true),
+            (None, None) => unreachable!(),
+        };
+        if in_left {
+            left_ix += 1;
+        }
+        if in_right {
+            right_ix += 1;
+        }
+        if keep(in_left, in_right) {
+            ret.push(node);
+        }
+    }
+    ret
+}
 
 #[allow(dead_code)]
 impl<N: HgNode> EdgeSet<N> {
     /// Creates an empty edge.
     pub fn new() -> Self {
-        EdgeSet(Vec::new())
+        EdgeSet(EdgeNodes::new())
     }
 
     /// Number of nodes in the edge
@@ -33,16 +70,21 @@ impl<N: HgNode> EdgeSet<N> {
         self.len() == 1
     }
 
+    /// Whether the nodes are stored inline rather than on the heap.
+    pub fn is_inline(&self) -> bool {
+        !self.0.spilled()
+    }
+
     pub fn get_first_node(&self) -> Option<N> {
         self.0.first().cloned()
     }
 
     pub fn node_set(&self) -> HashSet<N> {
-        self.0.clone().into_iter().collect()
+        self.0.iter().cloned().collect()
     }
 
     pub fn node_vec(&self) -> Vec<N> {
-        self.0.clone().into_iter().collect()
+        self.0.to_vec()
     }
 
     pub fn to_node_set(self) -> HashSet<N> {
@@ -50,7 +92,7 @@ impl<N: HgNode> EdgeSet<N> {
     }
 
     pub fn to_node_vec(self) -> Vec<N> {
-        self.0
+        self.0.into_vec()
     }
 
     pub fn add_node(&mut self, node: N) {
@@ -60,72 +102,28 @@ impl<N: HgNode> EdgeSet<N> {
     }
 
     pub fn contains_node(&self, node: &N) -> bool {
-        if self.0.len() == 0 {
-            return false;
-        }
-        let mut ret = false;
-        if let Ok(ix) = self.0.binary_search(node) {
-            if self.0[ix] == *node {
-                ret = true;
-            }
-        }
-        ret
+        self.0.binary_search(node).is_ok()
     }
 
     pub fn intersect_with(&mut self, rhs: &Self) {
-        let mut good_nodes = Vec::with_capacity(self.0.len());
-        for _ in 0..self.0.len() {
-            if let Some(node) = self.0.pop() {
-                if rhs.contains_node(&node) {
-                    good_nodes.insert(0, node);
-                }
-            }
-        }
-        self.0.clear();
-        self.0.append(&mut good_nodes);
+        *self = self.intersection(rhs);
     }
 
     pub fn intersection(&self, rhs: &Self) -> Self {
-        let mut ret = Vec::new();
-        let l1 = self.0.len();
-        let l2 = rhs.0.len();
-        let mut left_counter = 0;
-        let mut right_counter = 0;
-        for _ix in 0..(l1 + l2) {
-            if left_counter == l1 || right_counter == l2 {
-                break;
-            }
-            if self.0[left_counter] < rhs.0[right_counter] {
-                left_counter += 1;
-            } else if self.0[left_counter] > rhs.0[right_counter] {
-                right_counter += 1;
-            } else {
-                ret.push(self.0[left_counter].clone());
-                left_counter += 1;
-                right_counter += 1;
-            }
-        }
-        EdgeSet(ret)
+        EdgeSet(merge(&self.0, &rhs.0, |l, r| l && r))
     }
 
     pub fn union_with(&mut self, rhs: &Self) {
-        for node in rhs.0.iter() {
-            self.add_node(*node);
-        }
+        *self = self.union(rhs);
     }
 
-    // TODO: This is pretty unoptimal but it works.
     pub fn union(&self, rhs: &Self) -> Self {
-        let mut tot = HashSet::new();
-        for node in self.0.iter() {
-            tot.insert(*node);
-        }
-        for node in rhs.0.iter() {
-            tot.insert(*node);
-        }
-        let mut union: Vec<N> = tot.into_iter().collect();
-        union.sort();
-        EdgeSet(union)
+        EdgeSet(merge(&self.0, &rhs.0, |l, r| l || r))
+    }
+
+    /// The nodes of `self` that are not in `rhs`.
+    pub fn difference(&self, rhs: &Self) -> Self {
+        EdgeSet(merge(&self.0, &rhs.0, |l, r| l && !r))
     }
 
     /// If `rhs` is contained in self, returns the complement of rhs
@@ -134,15 +132,10 @@ impl<N: HgNode> EdgeSet<N> {
     /// This could lead to major bugs in the future, but the other option
     /// is that link returns an option.
     pub fn link(&self, rhs: &Self) -> Option<Self> {
-        if self.contains_strict(rhs) == false {
+        if !self.contains_strict(rhs) {
             return None;
         }
-        let mut ret_nodes = self.node_set();
-        for node in rhs.0.iter() {
-            ret_nodes.remove(node);
-        }
-        let nodes_vec: Vec<N> = ret_nodes.into_iter().collect();
-        Some(EdgeSet::from(nodes_vec))
+        Some(self.difference(rhs))
     }
 
     pub fn remove_node(&mut self, node: &N) {
@@ -152,25 +145,24 @@ impl<N: HgNode> EdgeSet<N> {
     }
 
     pub fn remove_nodes(&mut self, nodes: &Vec<N>) {
-        for node in nodes.iter() {
-            self.remove_node(node);
-        }
+        *self = self.difference(&EdgeSet::from(nodes));
         self.0.shrink_to_fit();
     }
 
     /// This is equivalent to other \subseteq self
     pub fn contains(&self, other: &Self) -> bool {
+        if other.0.len() > self.0.len() {
+            return false;
+        }
         let mut self_ix = 0;
-        let mut other_ix = 0;
-        while other_ix < other.0.len() {
-            if self_ix == self.0.len() {
-                return false;
-            }
-            if self.0[self_ix] == other.0[other_ix] {
-                other_ix += 1;
-            } else {
+        for node in other.0.iter() {
+            while self_ix < self.0.len() && self.0[self_ix] < *node {
                 self_ix += 1;
             }
+            if self_ix == self.0.len() || self.0[self_ix] != *node {
+                return false;
+            }
+            self_ix += 1;
         }
         true
     }
@@ -182,6 +174,193 @@ impl<N: HgNode> EdgeSet<N> {
     }
 }
 
+/// A bitset version of [`EdgeSet`] for hypergraphs with few nodes, where
+/// node `n` is bit [`dense_index`](HgNode::dense_index) of the set. The set
+/// operations are word-wise, and the first 128 nodes fit without allocating.
+/// Panics on node IDs that cannot be used as indices, like `Uuid`.
+#[derive(Debug, Clone, Hash, PartialEq, Eq)]
+pub struct BitEdgeSet<N: HgNode> {
+    /// Trailing zero words are always trimmed so equal sets compare equal.
+    words: SmallVec<[u64; 2]>,
+    _nodes: PhantomData<N>,
+}
+
+impl<N: HgNode> Default for BitEdgeSet<N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<N: HgNode> BitEdgeSet<N> {
+    pub fn new() -> Self {
+        BitEdgeSet {
+            words: SmallVec::new(),
+            _nodes: PhantomData,
+        }
+    }
+
+    fn from_words(mut words: SmallVec<[u64; 2]>) -> Self {
+        while words.last() == Some(&0) {
+            words.pop();
+        }
+        BitEdgeSet {
+            words,
+            _nodes: PhantomData,
+        }
+    }
+
+    fn bit(node: &N) -> (usize, u64) {
+        let ix = node
+            .dense_index()
+            .expect("Node ID type cannot be used in a BitEdgeSet.");
+        (ix / 64, 1 << (ix % 64))
+    }
+
+    /// Combines the words of both sets with `op`, treating missing words as
+    /// zero.
+    fn zip(&self, rhs: &Self, op: impl Fn(u64, u64) -> u64) -> Self {
+        let len = self.words.len().max(rhs.words.len());
+        Self::from_words(
+            (0..len)
+                .map(|ix| {
+                    let l = self.words.get(ix).copied().unwrap_or(0);
+                    let r = rhs.words.get(ix).copied().unwrap_or(0);
+                    op(l, r)
+                })
+                .collect(),
+        )
+    }
+
+    /// Number of nodes in the edge
+    pub fn len(&self) -> usize {
+        self.words
+            .iter()
+            .map(|word| word.count_ones() as usize)
+            .sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.words.is_empty()
+    }
+
+    pub fn is_node(&self) -> bool {
+        self.len() == 1
+    }
+
+    /// Iterates over the nodes in increasing order.
+    pub fn iter(&self) -> impl Iterator<Item = N> + '_ {
+        self.words.iter().enumerate().flat_map(|(word_ix, word)| {
+            let mut word = *word;
+            std::iter::from_fn(move || {
+                if word == 0 {
+                    return None;
+                }
+                let bit = word.trailing_zeros() as usize;
+                word &= word - 1;
+                Some(
+                    N::from_dense_index(word_ix * 64 + bit).expect("Bit outside of node ID range."),
+                )
+            })
+        })
+    }
+
+    pub fn get_first_node(&self) -> Option<N> {
+        self.iter().next()
+    }
+
+    pub fn node_vec(&self) -> Vec<N> {
+        self.iter().collect()
+    }
+
+    pub fn to_edge_set(&self) -> EdgeSet<N> {
+        EdgeSet(self.iter().collect())
+    }
+
+    pub fn add_node(&mut self, node: N) {
+        let (word, mask) = Self::bit(&node);
+        if word >= self.words.len() {
+            self.words.resize(word + 1, 0);
+        }
+        self.words[word] |= mask;
+    }
+
+    pub fn remove_node(&mut self, node: &N) {
+        let (word, mask) = Self::bit(node);
+        if let Some(bits) = self.words.get_mut(word) {
+            *bits &= !mask;
+        }
+        while self.words.last() == Some(&0) {
+            self.words.pop();
+        }
+    }
+
+    pub fn contains_node(&self, node: &N) -> bool {
+        let (word, mask) = Self::bit(node);
+        self.words.get(word).is_some_and(|bits| bits & mask != 0)
+    }
+
+    pub fn intersect_with(&mut self, rhs: &Self) {
+        *self = self.intersection(rhs);
+    }
+
+    pub fn intersection(&self, rhs: &Self) -> Self {
+        self.zip(rhs, |l, r| l & r)
+    }
+
+    pub fn union_with(&mut self, rhs: &Self) {
+        *self = self.union(rhs);
+    }
+
+    pub fn union(&self, rhs: &Self) -> Self {
+        self.zip(rhs, |l, r| l | r)
+    }
+
+    /// The nodes of `self` that are not in `rhs`.
+    pub fn difference(&self, rhs: &Self) -> Self {
+        self.zip(rhs, |l, r| l & !r)
+    }
+
+    /// The complement of `rhs` within `self` if `rhs` is a strict subset,
+    /// see [`EdgeSet::link`].
+    pub fn link(&self, rhs: &Self) -> Option<Self> {
+        if !self.contains_strict(rhs) {
+            return None;
+        }
+        Some(self.difference(rhs))
+    }
+
+    /// This is equivalent to other \subseteq self
+    pub fn contains(&self, other: &Self) -> bool {
+        other.words.len() <= self.words.len()
+            && other
+                .words
+                .iter()
+                .zip(self.words.iter())
+                .all(|(o, s)| o & !s == 0)
+    }
+
+    /// This is equivalent to other \subset self
+    pub fn contains_strict(&self, other: &Self) -> bool {
+        self.contains(other) && self != other
+    }
+}
+
+impl<N: HgNode> From<&EdgeSet<N>> for BitEdgeSet<N> {
+    fn from(value: &EdgeSet<N>) -> Self {
+        let mut set = BitEdgeSet::new();
+        for node in value.0.iter() {
+            set.add_node(*node);
+        }
+        set
+    }
+}
+
+impl<N: HgNode> From<&BitEdgeSet<N>> for EdgeSet<N> {
+    fn from(value: &BitEdgeSet<N>) -> Self {
+        value.to_edge_set()
+    }
+}
+
 impl<N: HgNode> Serialize for EdgeSet<N> {
     fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
     where
@@ -218,7 +397,7 @@ impl<'de, N: HgNode> Deserialize<'de> for EdgeSet<N> {
             data.remove(data.len() - 1);
         }
         if data.contains(",") {
-            let mut v: Vec<N> = data
+            let mut v: EdgeNodes<N> = data
                 .split(',')
                 .filter_map(|x| -> Option<N> {
                     if let Ok(number) = x.parse() {
@@ -232,10 +411,10 @@ impl<'de, N: HgNode> Deserialize<'de> for EdgeSet<N> {
             Ok(EdgeSet(v))
         } else {
             if let Ok(n) = data.parse::<N>() {
-                Ok(EdgeSet(vec![n]))
+                Ok(EdgeSet(smallvec::smallvec![n]))
             } else {
                 if data.len() == 0 {
-                    Ok(EdgeSet(vec![]))
+                    Ok(EdgeSet::new())
                 } else {
                     println!("Data: {:?}", data);
                     panic!("Could not parse single input.");
@@ -261,7 +440,7 @@ impl<N: HgNode> Display for EdgeSet<N> {
 impl<N: HgNode, R: AsRef<[N]>> From<R> for EdgeSet<N> {
     fn from(value: R) -> Self {
         let ref_value = value.as_ref();
-        let mut nodes: Vec<N> = ref_value.iter().cloned().collect();
+        let mut nodes: EdgeNodes<N> = ref_value.iter().cloned().collect();
         nodes.sort();
         nodes.dedup();
         EdgeSet(nodes)
@@ -271,7 +450,7 @@ impl<N: HgNode, R: AsRef<[N]>> From<R> for EdgeSet<N> {
 #[cfg(test)]
 mod test {
 
-    use super::EdgeSet;
+    use super::{BitEdgeSet, EdgeSet};
 
     #[test]
     fn test_contains() {
@@ -302,6 +481,48 @@ mod test {
         assert_eq!(e1, e5);
     }
 
+    #[test]
+    fn merge_operations() {
+        let e1 = EdgeSet::from([1_u32, 3, 5, 7]);
+        let e2 = EdgeSet::from([3_u32, 4, 5]);
+        assert_eq!(e1.union(&e2), EdgeSet::from([1, 3, 4, 5, 7]));
+        assert_eq!(e1.intersection(&e2), EdgeSet::from([3, 5]));
+        assert_eq!(e1.difference(&e2), EdgeSet::from([1, 7]));
+        assert_eq!(e1.link(&EdgeSet::from([3, 7])), Some(EdgeSet::from([1, 5])));
+        assert_eq!(e1.link(&e2), None);
+        let mut e3 = e1.clone();
+        e3.intersect_with(&e2);
+        assert_eq!(e3, EdgeSet::from([3, 5]));
+        e3.union_with(&EdgeSet::from([9, 1, 2, 0]));
+        assert_eq!(e3.node_vec(), vec![0, 1, 2, 3, 5, 9]);
+        assert!(!e3.is_inline());
+        assert!(e2.is_inline());
+        e3.remove_nodes(&vec![9, 0]);
+        assert_eq!(e3.node_vec(), vec![1, 2, 3, 5]);
+    }
+
+    #[test]
+    fn bitset_matches_sorted() {
+        let e1 = EdgeSet::from([1_u32, 3, 70, 130]);
+        let e2 = EdgeSet::from([3_u32, 70]);
+        let b1 = BitEdgeSet::from(&e1);
+        let b2 = BitEdgeSet::from(&e2);
+        assert_eq!(b1.len(), 4);
+        assert_eq!(b1.node_vec(), e1.node_vec());
+        assert!(b1.contains_strict(&b2));
+        assert!(!b2.contains(&b1));
+        assert_eq!(EdgeSet::from(&b1.union(&b2)), e1.union(&e2));
+        assert_eq!(EdgeSet::from(&b1.intersection(&b2)), e1.intersection(&e2));
+        assert_eq!(b1.link(&b2).map(|link| link.to_edge_set()), e1.link(&e2));
+        let mut b3 = b1.clone();
+        b3.remove_node(&130);
+        b3.remove_node(&1);
+        b3.add_node(3);
+        assert_eq!(b3, b2);
+        assert!(b3.contains_node(&70));
+        assert!(!b3.contains_node(&1000));
+    }
+
     #[test]
     fn maximal() {
         let e1 = EdgeSet::from([1_u8, 2, 3]);
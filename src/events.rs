@@ -0,0 +1,260 @@
+use std::sync::mpsc::{channel, Receiver, Sender};
+
+use crate::{HGraph, HgNode};
+
+/// A change to an [`HGraph`], sent to the subscribers registered with
+/// [`subscribe`](HGraph::subscribe) or
+/// [`event_channel`](HGraph::event_channel). Edges removed or shrunk as a
+/// side effect of removing or concatenating nodes get their own events.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum GraphEvent<NodeID, EdgeID> {
+    NodeAdded(NodeID),
+    NodeRemoved(NodeID),
+    EdgeAdded(EdgeID),
+    EdgeRemoved(EdgeID),
+    /// The nodes of an existing edge changed, for example when one of its
+    /// nodes was removed.
+    EdgeNodesChanged(EdgeID),
+    /// `removed` was merged into `into`, see
+    /// [`concatenate_nodes`](HGraph::concatenate_nodes). Sent after the
+    /// events for the edges that moved.
+    NodesConcatenated {
+        removed: NodeID,
+        into: NodeID,
+    },
+    /// The data of the node was replaced or mutably borrowed.
+    NodeDataChanged(NodeID),
+    /// The data of the edge was replaced or mutably borrowed.
+    EdgeDataChanged(EdgeID),
+    /// Nodes or edges were given new IDs, for example by
+    /// [`compact`](HGraph::compact), so any IDs held by a subscriber have to
+    /// be looked up again.
+    IdsReassigned,
+}
+
+type Callback<NodeID, EdgeID> = Box<dyn FnMut(&GraphEvent<NodeID, EdgeID>) + Send + Sync>;
+
+/// The subscribers of one graph. Clones start without subscribers, same as
+/// the instance tag.
+pub(crate) struct Observers<NodeID, EdgeID> {
+    callbacks: Vec<(usize, Callback<NodeID, EdgeID>)>,
+    channels: Vec<Sender<GraphEvent<NodeID, EdgeID>>>,
+    next_id: usize,
+    /// Holds back the events of a transaction until it commits.
+    held: Option<Vec<GraphEvent<NodeID, EdgeID>>>,
+}
+
+impl<NodeID, EdgeID> Default for Observers<NodeID, EdgeID> {
+    fn default() -> Self {
+        Observers {
+            callbacks: Vec::new(),
+            channels: Vec::new(),
+            next_id: 0,
+            held: None,
+        }
+    }
+}
+
+impl<NodeID, EdgeID> Clone for Observers<NodeID, EdgeID> {
+    fn clone(&self) -> Self {
+        Self::default()
+    }
+}
+
+impl<NodeID, EdgeID> std::fmt::Debug for Observers<NodeID, EdgeID> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Observers")
+            .field("callbacks", &self.callbacks.len())
+            .field("channels", &self.channels.len())
+            .finish()
+    }
+}
+
+impl<NodeID: Copy, EdgeID: Copy> Observers<NodeID, EdgeID> {
+    fn is_empty(&self) -> bool {
+        self.callbacks.is_empty() && self.channels.is_empty()
+    }
+
+    pub(crate) fn emit(&mut self, event: GraphEvent<NodeID, EdgeID>) {
+        if self.is_empty() {
+            return;
+        }
+        if let Some(held) = self.held.as_mut() {
+            held.push(event);
+            return;
+        }
+        for (_, callback) in self.callbacks.iter_mut() {
+            callback(&event);
+        }
+        // A dropped receiver unsubscribes its channel.
+        self.channels.retain(|sender| sender.send(event).is_ok());
+    }
+
+    pub(crate) fn hold(&mut self) {
+        self.held = Some(Vec::new());
+    }
+
+    /// Stops holding events back, sending the held ones if `send` is set
+    /// and dropping them otherwise.
+    pub(crate) fn release(&mut self, send: bool) {
+        let Some(held) = self.held.take() else {
+            return;
+        };
+        if send {
+            for event in held {
+                self.emit(event);
+            }
+        }
+    }
+}
+
+impl<NodeData, EdgeData, NodeID: HgNode, EdgeID: HgNode>
+    HGraph<NodeData, EdgeData, NodeID, EdgeID>
+{
+    /// Calls `callback` with every change made to this graph from now on,
+    /// returns an ID for [`unsubscribe`](HGraph::unsubscribe). Events of a
+    /// [`transaction`](HGraph::transaction) are only sent once it commits.
+    /// Subscribers are not carried over to clones or serialized.
+    pub fn subscribe(
+        &mut self,
+        callback: impl FnMut(&GraphEvent<NodeID, EdgeID>) + Send + Sync + 'static,
+    ) -> usize {
+        let id = self.observers.next_id;
+        self.observers.next_id += 1;
+        self.observers.callbacks.push((id, Box::new(callback)));
+        id
+    }
+
+    /// Removes a callback, returns `false` if there was none with that ID.
+    pub fn unsubscribe(&mut self, subscriber: usize) -> bool {
+        let before = self.observers.callbacks.len();
+        self.observers.callbacks.retain(|(id, _)| *id != subscriber);
+        self.observers.callbacks.len() != before
+    }
+
+    /// Same as [`subscribe`](HGraph::subscribe) but the events are sent
+    /// down a channel, which is dropped once the receiver is.
+    pub fn event_channel(&mut self) -> Receiver<GraphEvent<NodeID, EdgeID>> {
+        let (sender, receiver) = channel();
+        self.observers.channels.push(sender);
+        receiver
+    }
+
+    pub(crate) fn emit(&mut self, event: GraphEvent<NodeID, EdgeID>) {
+        self.observers.emit(event);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::panic::{catch_unwind, AssertUnwindSafe};
+    use std::sync::{Arc, Mutex};
+
+    use crate::{GraphEvent, HGraph, JournaledHGraph};
+
+    use GraphEvent::*;
+
+    #[test]
+    fn cascading_events() {
+        let mut hg = HGraph::<u8, u8>::new();
+        let seen = Arc::new(Mutex::new(Vec::new()));
+        let log = seen.clone();
+        let subscriber = hg.subscribe(move |event| log.lock().unwrap().push(*event));
+        let receiver = hg.event_channel();
+
+        hg.add_nodes(3);
+        let e0 = hg.add_edge([0], 0);
+        let e1 = hg.add_edge([0, 1], 1);
+        let e2 = hg.add_edge([1, 2], 2);
+        hg.add_edge([1, 0], 3);
+        hg.remove_node(0);
+        hg.concatenate_nodes(&1, &2);
+        let events: Vec<_> = receiver.try_iter().collect();
+        assert_eq!(&events, &*seen.lock().unwrap());
+        assert_eq!(
+            events[..7],
+            [
+                NodeAdded(0),
+                NodeAdded(1),
+                NodeAdded(2),
+                EdgeAdded(e0),
+                EdgeAdded(e1),
+                EdgeAdded(e2),
+                EdgeDataChanged(e1),
+            ]
+        );
+        let mut removal = events[7..10].to_vec();
+        removal.sort();
+        assert_eq!(
+            removal,
+            vec![NodeRemoved(0), EdgeRemoved(e0), EdgeNodesChanged(e1)]
+        );
+        assert!(events.contains(&EdgeRemoved(e1)));
+        assert!(events.contains(&EdgeNodesChanged(e2)));
+        assert_eq!(
+            events.last(),
+            Some(&NodesConcatenated {
+                removed: 1,
+                into: 2
+            })
+        );
+
+        assert!(hg.unsubscribe(subscriber));
+        drop(receiver);
+        hg.add_node(3);
+        assert_eq!(seen.lock().unwrap().len(), events.len());
+        assert!(hg.clone().event_channel().try_recv().is_err());
+    }
+
+    #[test]
+    fn transactions_and_undo() {
+        let mut hg = HGraph::<u8, u8>::new();
+        hg.add_nodes(2);
+        let receiver = hg.event_channel();
+        let failed: Result<(), String> = hg.transaction(|tx| {
+            tx.add_node(2)?;
+            tx.add_edge([0, 5], 0)?;
+            Ok(())
+        });
+        assert!(failed.is_err());
+        assert!(receiver.try_recv().is_err());
+        let edge = hg
+            .transaction(|tx| {
+                tx.set_node_data(&1, 7)?;
+                tx.add_edge([0, 1], 0)
+            })
+            .unwrap();
+        assert_eq!(
+            receiver.try_iter().collect::<Vec<_>>(),
+            vec![NodeDataChanged(1), EdgeAdded(edge)]
+        );
+        hg.get_edge_mut(&edge);
+        hg.compact();
+        assert_eq!(
+            receiver.try_iter().collect::<Vec<_>>(),
+            vec![EdgeDataChanged(edge)]
+        );
+
+        let panicked = catch_unwind(AssertUnwindSafe(|| {
+            hg.transaction::<(), _>(|tx| {
+                tx.add_node(4)?;
+                panic!("batch failed");
+            })
+        }));
+        assert!(panicked.is_err());
+        let node = hg.add_node(4);
+        assert_eq!(
+            receiver.try_iter().collect::<Vec<_>>(),
+            vec![NodeAdded(node)]
+        );
+
+        let mut journaled = JournaledHGraph::from(hg);
+        let node = journaled.add_node(3);
+        journaled.undo();
+        journaled.redo();
+        assert_eq!(
+            receiver.try_iter().collect::<Vec<_>>(),
+            vec![NodeAdded(node), NodeRemoved(node), NodeAdded(node)]
+        );
+    }
+}
@@ -0,0 +1,182 @@
+use fxhash::FxHashSet;
+
+use crate::{EdgeSet, HGraph, HgNode};
+
+impl<NodeData, EdgeData, NodeID: HgNode, EdgeID: HgNode>
+    HGraph<NodeData, EdgeData, NodeID, EdgeID>
+{
+    /// Returns the facets, the edges that are not strictly contained in any
+    /// other edge. Uses the tracked facet set if
+    /// [`enable_facet_tracking`](HGraph::enable_facet_tracking) was called,
+    /// otherwise checks every edge.
+    pub fn facets(&self) -> Vec<EdgeID> {
+        if let Some(facets) = &self.facets {
+            return facets.iter().cloned().collect();
+        }
+        self.edges
+            .iter()
+            .filter(|(_, edge)| !self.has_strict_superset(&edge.nodes))
+            .map(|(id, _)| *id)
+            .collect()
+    }
+
+    /// Same as [`facets`](HGraph::facets), the name used for simplicial
+    /// complexes.
+    pub fn toplexes(&self) -> Vec<EdgeID> {
+        self.facets()
+    }
+
+    /// Keeps the set of facets up to date as edges are added and removed so
+    /// that [`facets`](HGraph::facets) does not have to look at every edge.
+    /// Each update only looks at the edges contained in the changed edge,
+    /// which is fastest alongside the
+    /// [inclusion index](HGraph::enable_inclusion_index). The tracked set is
+    /// not serialized.
+    pub fn enable_facet_tracking(&mut self) {
+        let facets = self.facets().into_iter().collect();
+        self.facets = Some(facets);
+    }
+
+    pub fn disable_facet_tracking(&mut self) {
+        self.facets = None;
+    }
+
+    /// Called once `edge_id` has been added with its current nodes.
+    pub(crate) fn track_added_facet(&mut self, edge_id: EdgeID) {
+        if self.facets.is_none() {
+            return;
+        }
+        let nodes = &self.edges.get(&edge_id).unwrap().nodes;
+        if self.has_strict_superset(nodes) {
+            return;
+        }
+        // An edge with the same nodes stays a facet next to this one.
+        let covered: Vec<EdgeID> = self
+            .subsets_of_nodes(&nodes.0)
+            .into_iter()
+            .filter(|id| self.edges.get(id).unwrap().nodes.len() < nodes.len())
+            .collect();
+        let facets = self.facets.as_mut().unwrap();
+        for id in covered {
+            facets.remove(&id);
+        }
+        facets.insert(edge_id);
+    }
+
+    /// Called while `edge_id` still has its nodes but is about to be removed
+    /// or changed. Edges that were only covered by it become facets.
+    pub(crate) fn track_removed_facet(&mut self, edge_id: EdgeID) {
+        let Some(facets) = &self.facets else {
+            return;
+        };
+        if !facets.contains(&edge_id) {
+            return;
+        }
+        let nodes = &self.edges.get(&edge_id).unwrap().nodes;
+        let uncovered: Vec<EdgeID> = self
+            .subsets_of_nodes(&nodes.0)
+            .into_iter()
+            .filter(|id| *id != edge_id)
+            .filter(|id| {
+                let sub_nodes = &self.edges.get(id).unwrap().nodes;
+                !self.is_covered_without(sub_nodes, *id, edge_id)
+            })
+            .collect();
+        let facets = self.facets.as_mut().unwrap();
+        facets.remove(&edge_id);
+        facets.extend(uncovered);
+    }
+
+    /// Whether some edge other than `edge_id` and `ignored` strictly contains
+    /// `nodes`.
+    fn is_covered_without(
+        &self,
+        nodes: &EdgeSet<NodeID>,
+        edge_id: EdgeID,
+        ignored: EdgeID,
+    ) -> bool {
+        self.supersets_of_nodes(&nodes.0).into_iter().any(|id| {
+            id != edge_id && id != ignored && self.edges.get(&id).unwrap().nodes.len() > nodes.len()
+        })
+    }
+}
+
+impl<NodeData, EdgeData, NodeID, EdgeID> HGraph<NodeData, EdgeData, NodeID, EdgeID>
+where
+    NodeID: HgNode,
+    EdgeID: HgNode,
+    NodeData: Clone,
+    EdgeData: Clone,
+{
+    /// Returns a copy of the hypergraph reduced to a simple, or Sperner,
+    /// hypergraph by dropping every edge contained in another. All nodes and
+    /// IDs are kept.
+    pub fn to_simple(&self) -> HGraph<NodeData, EdgeData, NodeID, EdgeID> {
+        let facets: FxHashSet<EdgeID> = self.facets().into_iter().collect();
+        let mut simple = self.clone();
+        for edge_id in self.edges.keys() {
+            if !facets.contains(edge_id) {
+                simple.remove_edge(*edge_id);
+            }
+        }
+        simple
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{sorted, HGraph};
+
+    #[test]
+    fn facets_and_simple() {
+        let mut hg = HGraph::<(), ()>::new();
+        hg.add_nodes(5);
+        let e0 = hg.add_edge([0, 1], ());
+        let e1 = hg.add_edge([0, 1, 2], ());
+        let e2 = hg.add_edge([2, 3], ());
+        let e3 = hg.add_edge([3], ());
+        assert_eq!(sorted(hg.facets()), vec![e1, e2]);
+        assert_eq!(hg.toplexes().len(), 2);
+
+        let simple = hg.to_simple();
+        assert_eq!(simple.num_nodes(), 5);
+        assert_eq!(sorted(simple.edges()), vec![e1, e2]);
+        assert!(simple.get_edge(&e0).is_none());
+        assert!(simple.get_edge(&e3).is_none());
+    }
+
+    #[test]
+    fn tracking_follows_mutations() {
+        for inclusion_index in [false, true] {
+            let mut hg = HGraph::<(), ()>::new();
+            hg.add_nodes(5);
+            if inclusion_index {
+                hg.enable_inclusion_index();
+            }
+            hg.enable_facet_tracking();
+            let e0 = hg.add_edge([0, 1], ());
+            let e1 = hg.add_edge([1, 2], ());
+            assert_eq!(sorted(hg.facets()), vec![e0, e1]);
+            let e2 = hg.add_edge([0, 1, 2], ());
+            assert_eq!(hg.facets(), vec![e2]);
+            let e3 = hg.add_edge([2], ());
+            assert_eq!(hg.facets(), vec![e2]);
+            hg.remove_edge(e2);
+            assert_eq!(sorted(hg.facets()), vec![e0, e1]);
+            hg.remove_node(0);
+            assert_eq!(sorted(hg.facets()), vec![e1]);
+            hg.remove_edge(e1);
+            assert_eq!(sorted(hg.facets()), vec![e0, e3]);
+
+            // Removing nodes 3 and 4 leaves e4 and e5 with the same nodes.
+            let e4 = hg.add_edge([1, 2, 3], ());
+            let e5 = hg.add_edge([1, 2, 4], ());
+            hg.remove_node(3);
+            hg.remove_node(4);
+            assert_eq!(sorted(hg.facets()), vec![e4, e5]);
+            let mut untracked = hg.clone();
+            untracked.disable_facet_tracking();
+            assert_eq!(sorted(hg.facets()), sorted(untracked.facets()));
+        }
+    }
+}
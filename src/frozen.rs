@@ -0,0 +1,531 @@
+use fxhash::FxHashSet;
+
+use crate::hgraph::{Edge, Node};
+use crate::id_map::IdMap;
+use crate::id_policy::IdAllocator;
+use crate::{EdgeSet, HGraph, HgNode, HyperGraph};
+
+/// A read-only snapshot of an [`HGraph`] stored as two compressed sparse row
+/// incidence arrays, edge to nodes and node to edges. Nodes and edges are
+/// remapped to dense `u32` indices in increasing ID order, so the nodes of an
+/// edge and the edges of a node are contiguous sorted slices and IDs are found
+/// by binary search instead of hashing. Created with [`HGraph::freeze`] and
+/// turned back into a mutable graph with [`thaw`](FrozenHGraph::thaw).
+#[derive(Debug, Clone)]
+pub struct FrozenHGraph<NodeData, EdgeData, NodeID: HgNode = u32, EdgeID: HgNode = u64> {
+    node_ids: Vec<NodeID>,
+    edge_ids: Vec<EdgeID>,
+    node_data: Vec<NodeData>,
+    edge_data: Vec<EdgeData>,
+    /// The nodes of edge `e` are `edge_nodes[edge_offsets[e]..edge_offsets[e + 1]]`.
+    edge_offsets: Vec<usize>,
+    edge_nodes: Vec<u32>,
+    /// The edges of node `n` are `node_edges[node_offsets[n]..node_offsets[n + 1]]`.
+    node_offsets: Vec<usize>,
+    node_edges: Vec<u32>,
+    next_node_id: NodeID,
+    next_edge_id: EdgeID,
+    node_alloc: IdAllocator<NodeID>,
+    edge_alloc: IdAllocator<EdgeID>,
+}
+
+fn dense(ix: usize) -> u32 {
+    u32::try_from(ix).expect("FrozenHGraph supports at most u32::MAX nodes and edges.")
+}
+
+/// Whether every element of the sorted slice `small` is in the sorted slice
+/// `big`.
+fn is_sorted_subset(small: &[u32], big: &[u32]) -> bool {
+    if small.len() > big.len() {
+        return false;
+    }
+    let mut big = big.iter();
+    small.iter().all(|x| big.any(|y| y == x))
+}
+
+impl<NodeData, EdgeData, NodeID: HgNode, EdgeID: HgNode>
+    HGraph<NodeData, EdgeData, NodeID, EdgeID>
+{
+    /// Consumes the hypergraph and packs it into a [`FrozenHGraph`]. Any
+    /// enabled indices are dropped, they can be turned back on after
+    /// [`thaw`](FrozenHGraph::thaw).
+    pub fn freeze(self) -> FrozenHGraph<NodeData, EdgeData, NodeID, EdgeID> {
+        let mut nodes: Vec<(NodeID, Node<NodeData, EdgeID>)> = self.nodes.into_iter().collect();
+        nodes.sort_by_key(|(id, _)| *id);
+        let mut edges: Vec<(EdgeID, Edge<NodeID, EdgeData>)> = self.edges.into_iter().collect();
+        edges.sort_by_key(|(id, _)| *id);
+
+        let node_ids: Vec<NodeID> = nodes.iter().map(|(id, _)| *id).collect();
+        let edge_ids: Vec<EdgeID> = edges.iter().map(|(id, _)| *id).collect();
+        let mut edge_offsets = Vec::with_capacity(edges.len() + 1);
+        let mut edge_nodes = Vec::new();
+        let mut degrees = vec![0; node_ids.len()];
+        edge_offsets.push(0);
+        for (_, edge) in edges.iter() {
+            for node in edge.nodes.0.iter() {
+                let ix = node_ids
+                    .binary_search(node)
+                    .expect("Edge contains a node that is not in the graph.");
+                degrees[ix] += 1;
+                edge_nodes.push(dense(ix));
+            }
+            edge_offsets.push(edge_nodes.len());
+        }
+
+        // Walking the edges in order fills each node's slice already sorted.
+        let mut node_offsets = Vec::with_capacity(node_ids.len() + 1);
+        node_offsets.push(0);
+        for degree in degrees.iter() {
+            node_offsets.push(node_offsets.last().unwrap() + degree);
+        }
+        let mut fill = node_offsets.clone();
+        let mut node_edges = vec![0; edge_nodes.len()];
+        for edge in 0..edge_ids.len() {
+            for node in edge_nodes[edge_offsets[edge]..edge_offsets[edge + 1]].iter() {
+                let slot = &mut fill[*node as usize];
+                node_edges[*slot] = dense(edge);
+                *slot += 1;
+            }
+        }
+
+        FrozenHGraph {
+            node_ids,
+            edge_ids,
+            node_data: nodes.into_iter().map(|(_, node)| node.data).collect(),
+            edge_data: edges.into_iter().map(|(_, edge)| edge.data).collect(),
+            edge_offsets,
+            edge_nodes,
+            node_offsets,
+            node_edges,
+            next_node_id: self.next_node_id,
+            next_edge_id: self.next_edge_id,
+            node_alloc: self.node_alloc,
+            edge_alloc: self.edge_alloc,
+        }
+    }
+}
+
+impl<NodeData, EdgeData, NodeID: HgNode, EdgeID: HgNode>
+    FrozenHGraph<NodeData, EdgeData, NodeID, EdgeID>
+{
+    /// Unpacks the snapshot into a mutable [`HGraph`] with the same IDs and
+    /// data, which continues handing out IDs where the original graph would
+    /// have.
+    pub fn thaw(self) -> HGraph<NodeData, EdgeData, NodeID, EdgeID> {
+        let mut hg = HGraph::new();
+        hg.next_node_id = self.next_node_id;
+        hg.next_edge_id = self.next_edge_id;
+        hg.node_alloc = self.node_alloc;
+        hg.edge_alloc = self.edge_alloc;
+        let mut edges: IdMap<EdgeID, Edge<NodeID, EdgeData>> = IdMap::default();
+        edges.reserve(self.edge_ids.len());
+        for (ix, data) in self.edge_data.into_iter().enumerate() {
+            let nodes = self.edge_nodes[self.edge_offsets[ix]..self.edge_offsets[ix + 1]]
+                .iter()
+                .map(|node| self.node_ids[*node as usize])
+                .collect();
+            edges.insert(
+                self.edge_ids[ix],
+                Edge {
+                    nodes: EdgeSet(nodes),
+                    data,
+                },
+            );
+        }
+        let mut nodes: IdMap<NodeID, Node<NodeData, EdgeID>> = IdMap::default();
+        nodes.reserve(self.node_ids.len());
+        for (ix, data) in self.node_data.into_iter().enumerate() {
+            let containing_edges: FxHashSet<EdgeID> = self.node_edges
+                [self.node_offsets[ix]..self.node_offsets[ix + 1]]
+                .iter()
+                .map(|edge| self.edge_ids[*edge as usize])
+                .collect();
+            nodes.insert(
+                self.node_ids[ix],
+                Node {
+                    containing_edges,
+                    data,
+                },
+            );
+        }
+        hg.edges = edges;
+        hg.nodes = nodes;
+        hg
+    }
+
+    pub fn get_node(&self, node: &NodeID) -> Option<&NodeData> {
+        self.node_index(node).map(|ix| &self.node_data[ix])
+    }
+
+    pub fn get_edge(&self, edge: &EdgeID) -> Option<&EdgeData> {
+        self.edge_index(edge).map(|ix| &self.edge_data[ix])
+    }
+
+    /// Returns the ID of the edge consisting of exactly the provided nodes, if
+    /// there is one.
+    pub fn find_id(&self, nodes: impl AsRef<[NodeID]>) -> Option<EdgeID> {
+        let query = self.dense_query(nodes.as_ref())?;
+        let first = *query.first()?;
+        self.node_slice(first as usize)
+            .iter()
+            .find(|edge| self.edge_slice(**edge as usize) == &query[..])
+            .map(|edge| self.edge_ids[*edge as usize])
+    }
+
+    fn node_index(&self, node: &NodeID) -> Option<usize> {
+        self.node_ids.binary_search(node).ok()
+    }
+
+    fn edge_index(&self, edge: &EdgeID) -> Option<usize> {
+        self.edge_ids.binary_search(edge).ok()
+    }
+
+    fn edge_slice(&self, edge: usize) -> &[u32] {
+        &self.edge_nodes[self.edge_offsets[edge]..self.edge_offsets[edge + 1]]
+    }
+
+    fn node_slice(&self, node: usize) -> &[u32] {
+        &self.node_edges[self.node_offsets[node]..self.node_offsets[node + 1]]
+    }
+
+    fn edge_node_ids(&self, edge: usize) -> Vec<NodeID> {
+        self.edge_slice(edge)
+            .iter()
+            .map(|node| self.node_ids[*node as usize])
+            .collect()
+    }
+
+    /// The sorted, deduplicated dense indices of the provided nodes, `None` if
+    /// any of them is missing.
+    fn dense_query(&self, nodes: &[NodeID]) -> Option<Vec<u32>> {
+        let mut query = nodes
+            .iter()
+            .map(|node| self.node_index(node).map(dense))
+            .collect::<Option<Vec<u32>>>()?;
+        query.sort();
+        query.dedup();
+        Some(query)
+    }
+
+    /// Dense indices of the edges strictly containing `query`, found by
+    /// scanning the edges of the node of smallest degree.
+    fn strict_supersets(&self, query: &[u32]) -> Vec<u32> {
+        let rarest = query
+            .iter()
+            .min_by_key(|node| self.node_slice(**node as usize).len());
+        match rarest {
+            Some(node) => self
+                .node_slice(*node as usize)
+                .iter()
+                .filter(|edge| {
+                    let nodes = self.edge_slice(**edge as usize);
+                    nodes.len() > query.len() && is_sorted_subset(query, nodes)
+                })
+                .cloned()
+                .collect(),
+            None => (0..self.edge_ids.len())
+                .filter(|edge| !self.edge_slice(*edge).is_empty())
+                .map(dense)
+                .collect(),
+        }
+    }
+
+    /// Dense indices of the edges with fewer than `below` nodes that are
+    /// subsets of `query`. Each such edge is found through every one of its
+    /// nodes, so it is only kept when reached through its first node.
+    fn subsets_below(&self, query: &[u32], below: usize) -> Vec<u32> {
+        let mut subsets = Vec::new();
+        for node in query.iter() {
+            for edge in self.node_slice(*node as usize).iter() {
+                let nodes = self.edge_slice(*edge as usize);
+                if nodes[0] == *node && nodes.len() < below && is_sorted_subset(nodes, query) {
+                    subsets.push(*edge);
+                }
+            }
+        }
+        subsets
+    }
+
+    fn has_strict_superset(&self, nodes: &[u32]) -> bool {
+        match nodes.first() {
+            Some(node) => self.node_slice(*node as usize).iter().any(|edge| {
+                let other = self.edge_slice(*edge as usize);
+                other.len() > nodes.len() && is_sorted_subset(nodes, other)
+            }),
+            None => self.edge_offsets.windows(2).any(|w| w[1] > w[0]),
+        }
+    }
+
+    fn to_edge_ids(&self, edges: Vec<u32>) -> Vec<EdgeID> {
+        edges
+            .into_iter()
+            .map(|edge| self.edge_ids[edge as usize])
+            .collect()
+    }
+
+    fn links(&self, query: &[u32]) -> Vec<(EdgeID, Vec<NodeID>)> {
+        self.strict_supersets(query)
+            .into_iter()
+            .map(|edge| {
+                let link = self
+                    .edge_slice(edge as usize)
+                    .iter()
+                    .filter(|node| query.binary_search(node).is_err())
+                    .map(|node| self.node_ids[*node as usize])
+                    .collect();
+                (self.edge_ids[edge as usize], link)
+            })
+            .collect()
+    }
+
+    fn maximal(&self, query: &[u32]) -> Vec<EdgeID> {
+        let maximal = self
+            .strict_supersets(query)
+            .into_iter()
+            .filter(|edge| !self.has_strict_superset(self.edge_slice(*edge as usize)))
+            .collect();
+        self.to_edge_ids(maximal)
+    }
+
+    fn of_size(&self, edges: Vec<u32>, size: usize) -> Vec<Vec<NodeID>> {
+        edges
+            .into_iter()
+            .filter(|edge| self.edge_slice(*edge as usize).len() == size)
+            .map(|edge| self.edge_node_ids(edge as usize))
+            .collect()
+    }
+}
+
+impl<NodeData, EdgeData, NodeID: HgNode, EdgeID: HgNode> HyperGraph
+    for FrozenHGraph<NodeData, EdgeData, NodeID, EdgeID>
+{
+    type NodeID = NodeID;
+    type EdgeID = EdgeID;
+
+    fn query_edge(&self, edge: &Self::EdgeID) -> Option<Vec<Self::NodeID>> {
+        self.edge_index(edge).map(|ix| self.edge_node_ids(ix))
+    }
+
+    fn node_ids(&self) -> impl Iterator<Item = Self::NodeID> + '_ {
+        self.node_ids.iter().cloned()
+    }
+
+    fn edge_ids(&self) -> impl Iterator<Item = Self::EdgeID> + '_ {
+        self.edge_ids.iter().cloned()
+    }
+
+    fn num_nodes(&self) -> usize {
+        self.node_ids.len()
+    }
+
+    fn num_edges(&self) -> usize {
+        self.edge_ids.len()
+    }
+
+    fn degree(&self, node: &Self::NodeID) -> Option<usize> {
+        self.node_index(node).map(|ix| self.node_slice(ix).len())
+    }
+
+    fn edge_size(&self, edge: &Self::EdgeID) -> Option<usize> {
+        self.edge_index(edge).map(|ix| self.edge_slice(ix).len())
+    }
+
+    fn incident_edges(&self, node: &Self::NodeID) -> Vec<Self::EdgeID> {
+        match self.node_index(node) {
+            Some(ix) => self.to_edge_ids(self.node_slice(ix).to_vec()),
+            None => Vec::new(),
+        }
+    }
+
+    fn containing_edges_of_nodes(&self, nodes: impl AsRef<[Self::NodeID]>) -> Vec<Self::EdgeID> {
+        match self.dense_query(nodes.as_ref()) {
+            Some(query) => self.to_edge_ids(self.strict_supersets(&query)),
+            None => Vec::new(),
+        }
+    }
+
+    fn containing_edges(&self, edge: &Self::EdgeID) -> Vec<Self::EdgeID> {
+        match self.edge_index(edge) {
+            Some(ix) => self.to_edge_ids(self.strict_supersets(self.edge_slice(ix))),
+            None => Vec::new(),
+        }
+    }
+
+    fn link(&self, edge: &Self::EdgeID) -> Vec<(Self::EdgeID, Vec<Self::NodeID>)> {
+        match self.edge_index(edge) {
+            Some(ix) => self.links(self.edge_slice(ix)),
+            None => Vec::new(),
+        }
+    }
+
+    fn link_of_nodes(
+        &self,
+        nodes: impl AsRef<[Self::NodeID]>,
+    ) -> Vec<(Self::EdgeID, Vec<Self::NodeID>)> {
+        match self.dense_query(nodes.as_ref()) {
+            Some(query) => self.links(&query),
+            None => Vec::new(),
+        }
+    }
+
+    fn maximal_edges(&self, edge_id: &Self::EdgeID) -> Vec<Self::EdgeID> {
+        match self.edge_index(edge_id) {
+            Some(ix) => self.maximal(self.edge_slice(ix)),
+            None => Vec::new(),
+        }
+    }
+
+    fn maximal_edges_of_nodes(&self, nodes: impl AsRef<[Self::NodeID]>) -> Vec<Self::EdgeID> {
+        match self.dense_query(nodes.as_ref()) {
+            Some(query) => self.maximal(&query),
+            None => Vec::new(),
+        }
+    }
+
+    fn edges_of_size(&self, card: usize) -> Vec<Self::EdgeID> {
+        (0..self.edge_ids.len())
+            .filter(|ix| self.edge_slice(*ix).len() == card)
+            .map(|ix| self.edge_ids[ix])
+            .collect()
+    }
+
+    fn skeleton(&self, cardinality: usize) -> Vec<Self::EdgeID> {
+        (0..self.edge_ids.len())
+            .filter(|ix| self.edge_slice(*ix).len() <= cardinality)
+            .map(|ix| self.edge_ids[ix])
+            .collect()
+    }
+
+    fn boundary_up(&self, edge_id: &Self::EdgeID) -> Vec<Vec<Self::NodeID>> {
+        let Some(ix) = self.edge_index(edge_id) else {
+            return Vec::new();
+        };
+        let nodes = self.edge_slice(ix);
+        self.of_size(self.strict_supersets(nodes), nodes.len() + 1)
+    }
+
+    fn boundary_down(&self, edge_id: &Self::EdgeID) -> Vec<Vec<Self::NodeID>> {
+        let Some(ix) = self.edge_index(edge_id) else {
+            return Vec::new();
+        };
+        let nodes = self.edge_slice(ix);
+        self.of_size(
+            self.subsets_below(nodes, nodes.len()),
+            nodes.len().saturating_sub(1),
+        )
+    }
+
+    fn boundary_up_of_nodes(&self, nodes: impl AsRef<[Self::NodeID]>) -> Vec<Vec<Self::NodeID>> {
+        let Some(query) = self.dense_query(nodes.as_ref()) else {
+            return Vec::new();
+        };
+        self.of_size(self.strict_supersets(&query), query.len() + 1)
+    }
+
+    fn boundary_down_of_nodes(&self, nodes: impl AsRef<[Self::NodeID]>) -> Vec<Vec<Self::NodeID>> {
+        let Some(query) = self.dense_query(nodes.as_ref()) else {
+            return Vec::new();
+        };
+        self.of_size(
+            self.subsets_below(&query, query.len()),
+            query.len().saturating_sub(1),
+        )
+    }
+
+    fn faces_of_nodes(&self, nodes: impl AsRef<[Self::NodeID]>) -> Vec<Self::EdgeID> {
+        // A face only needs its own nodes to be present, so missing nodes are
+        // skipped but still count towards the size of the input.
+        let len = EdgeSet::from(nodes.as_ref()).len();
+        let mut query: Vec<u32> = nodes
+            .as_ref()
+            .iter()
+            .filter_map(|node| self.node_index(node).map(dense))
+            .collect();
+        query.sort();
+        query.dedup();
+        self.to_edge_ids(self.subsets_below(&query, len))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{sorted, HGraph, HyperGraph};
+
+    fn graph() -> HGraph<u8, char> {
+        let mut hg = HGraph::new();
+        for x in 0..7 {
+            hg.add_node(x);
+        }
+        hg.add_edge([0], 'z');
+        hg.add_edge([0, 1], 'a');
+        hg.add_edge([0, 1, 2], 'b');
+        hg.add_edge([0, 1, 3], 'c');
+        hg.add_edge([0, 1, 2, 3], 'd');
+        hg.add_edge([1, 2, 5], 'e');
+        hg.add_edge([3, 4], 'f');
+        hg.remove_node(6);
+        hg
+    }
+
+    #[test]
+    fn queries_match_hgraph() {
+        let hg = graph();
+        let frozen = hg.clone().freeze();
+        assert_eq!(frozen.num_nodes(), 6);
+        assert_eq!(frozen.num_edges(), 7);
+        for edge in hg.edge_ids() {
+            assert_eq!(frozen.query_edge(&edge), hg.query_edge(&edge));
+            assert_eq!(frozen.get_edge(&edge), hg.get_edge(&edge));
+            assert_eq!(
+                sorted(frozen.containing_edges(&edge)),
+                sorted(hg.containing_edges(&edge))
+            );
+            assert_eq!(sorted(frozen.link(&edge)), sorted(hg.link(&edge)));
+            assert_eq!(
+                sorted(frozen.maximal_edges(&edge)),
+                sorted(hg.maximal_edges(&edge))
+            );
+            assert_eq!(sorted(frozen.faces(&edge)), sorted(hg.faces(&edge)));
+            assert_eq!(
+                sorted(frozen.boundary_up(&edge)),
+                sorted(hg.boundary_up(&edge))
+            );
+        }
+        for node in hg.node_ids() {
+            assert_eq!(frozen.degree(&node), hg.degree(&node));
+            assert_eq!(
+                sorted(frozen.incident_edges(&node)),
+                sorted(hg.incident_edges(&node))
+            );
+        }
+        assert_eq!(
+            sorted(frozen.boundary_down(&4)),
+            vec![vec![0, 1, 2], vec![0, 1, 3]]
+        );
+        assert_eq!(
+            sorted(frozen.faces_of_nodes([0, 1, 2, 9])),
+            sorted(hg.faces_of_nodes([0, 1, 2, 9]))
+        );
+        assert_eq!(
+            sorted(frozen.link_of_nodes([1])),
+            sorted(hg.link_of_nodes([1]))
+        );
+        assert_eq!(sorted(frozen.skeleton(2)), sorted(hg.skeleton(2)));
+        assert_eq!(frozen.find_id([1, 0]), Some(1));
+        assert_eq!(frozen.degree(&6), None);
+        assert!(frozen.containing_edges_of_nodes([0, 6]).is_empty());
+    }
+
+    #[test]
+    fn thaw_round_trip() {
+        let hg = graph();
+        let mut thawed = hg.clone().freeze().thaw();
+        assert_eq!(sorted(thawed.nodes()), sorted(hg.nodes()));
+        assert_eq!(sorted(thawed.edges()), sorted(hg.edges()));
+        assert_eq!(thawed.get_node(&5), Some(&5));
+        assert_eq!(thawed.find_id([3, 4]), Some(6));
+        assert_eq!(thawed.degree(&1), hg.degree(&1));
+        assert_eq!(thawed.add_node(6), 7);
+        assert_eq!(thawed.add_edge([5, 7], 'g'), 7);
+    }
+}
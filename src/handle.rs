@@ -0,0 +1,212 @@
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use crate::{HGraph, HgNode};
+
+static NEXT_TAG: AtomicU64 = AtomicU64::new(0);
+
+/// Identifies one graph instance. Every new, cloned, filtered or deserialized
+/// graph gets a fresh tag, so cloning a tag hands out a new one instead of
+/// copying it.
+#[derive(Debug, PartialEq, Eq)]
+pub(crate) struct InstanceTag(u64);
+
+impl InstanceTag {
+    pub(crate) fn new() -> Self {
+        InstanceTag(NEXT_TAG.fetch_add(1, Ordering::Relaxed))
+    }
+}
+
+impl Default for InstanceTag {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Clone for InstanceTag {
+    fn clone(&self) -> Self {
+        Self::new()
+    }
+}
+
+/// A checked reference to a node, created with
+/// [`node_handle`](HGraph::node_handle). Resolving it with
+/// [`resolve_node`](HGraph::resolve_node) fails if it comes from a different
+/// graph instance, if the graph has since reassigned its IDs, or if the node
+/// was removed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct NodeHandle<NodeID: HgNode> {
+    graph: u64,
+    generation: u32,
+    id: NodeID,
+    /// Times the ID had been reused when the handle was made.
+    reuse: u32,
+}
+
+/// The edge version of [`NodeHandle`], created with
+/// [`edge_handle`](HGraph::edge_handle).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct EdgeHandle<EdgeID: HgNode> {
+    graph: u64,
+    generation: u32,
+    id: EdgeID,
+    reuse: u32,
+}
+
+impl<NodeID: HgNode> NodeHandle<NodeID> {
+    /// The raw ID, unchecked.
+    pub fn id(&self) -> NodeID {
+        self.id
+    }
+
+    pub fn graph_tag(&self) -> u64 {
+        self.graph
+    }
+
+    pub fn generation(&self) -> u32 {
+        self.generation
+    }
+}
+
+impl<EdgeID: HgNode> EdgeHandle<EdgeID> {
+    /// The raw ID, unchecked.
+    pub fn id(&self) -> EdgeID {
+        self.id
+    }
+
+    pub fn graph_tag(&self) -> u64 {
+        self.graph
+    }
+
+    pub fn generation(&self) -> u32 {
+        self.generation
+    }
+}
+
+impl<NodeData, EdgeData, NodeID: HgNode, EdgeID: HgNode>
+    HGraph<NodeData, EdgeData, NodeID, EdgeID>
+{
+    /// The tag of this graph instance, shared by every handle it creates.
+    pub fn graph_tag(&self) -> u64 {
+        self.tag.0
+    }
+
+    /// Counts the times this graph has reassigned its IDs, any handle made
+    /// before the latest reassignment is stale.
+    pub fn generation(&self) -> u32 {
+        self.generation
+    }
+
+    /// Returns a handle for the node, `None` if the node is not present.
+    pub fn node_handle(&self, node: NodeID) -> Option<NodeHandle<NodeID>> {
+        self.nodes.contains_key(&node).then_some(NodeHandle {
+            graph: self.tag.0,
+            generation: self.generation,
+            id: node,
+            reuse: self.node_alloc.reuses(&node),
+        })
+    }
+
+    /// Returns a handle for the edge, `None` if the edge is not present.
+    pub fn edge_handle(&self, edge: EdgeID) -> Option<EdgeHandle<EdgeID>> {
+        self.edges.contains_key(&edge).then_some(EdgeHandle {
+            graph: self.tag.0,
+            generation: self.generation,
+            id: edge,
+            reuse: self.edge_alloc.reuses(&edge),
+        })
+    }
+
+    /// Returns the ID behind the handle, or why it cannot be used with this
+    /// graph.
+    pub fn resolve_node(&self, handle: &NodeHandle<NodeID>) -> Result<NodeID, String> {
+        self.check_handle(handle.graph, handle.generation)?;
+        if !self.nodes.contains_key(&handle.id)
+            || self.node_alloc.reuses(&handle.id) != handle.reuse
+        {
+            return Err(format!("Node {:?} has been removed.", handle.id));
+        }
+        Ok(handle.id)
+    }
+
+    /// Returns the ID behind the handle, or why it cannot be used with this
+    /// graph.
+    pub fn resolve_edge(&self, handle: &EdgeHandle<EdgeID>) -> Result<EdgeID, String> {
+        self.check_handle(handle.graph, handle.generation)?;
+        if !self.edges.contains_key(&handle.id)
+            || self.edge_alloc.reuses(&handle.id) != handle.reuse
+        {
+            return Err(format!("Edge {:?} has been removed.", handle.id));
+        }
+        Ok(handle.id)
+    }
+
+    fn check_handle(&self, graph: u64, generation: u32) -> Result<(), String> {
+        if graph != self.tag.0 {
+            return Err(format!(
+                "Handle belongs to graph {} but was used with graph {}.",
+                graph, self.tag.0
+            ));
+        }
+        if generation != self.generation {
+            return Err(format!(
+                "Handle is from generation {} but the graph is at generation {}.",
+                generation, self.generation
+            ));
+        }
+        Ok(())
+    }
+
+    /// Called by anything that gives existing nodes or edges new IDs, which
+    /// invalidates every handle.
+    pub(crate) fn bump_generation(&mut self) {
+        self.generation = self.generation.wrapping_add(1);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::HGraph;
+
+    #[test]
+    fn handles_detect_misuse() {
+        let mut hg = HGraph::<u8, ()>::new();
+        let n0 = hg.add_node(0);
+        let n1 = hg.add_node(1);
+        let e0 = hg.add_edge([n0, n1], ());
+        let node = hg.node_handle(n1).unwrap();
+        let edge = hg.edge_handle(e0).unwrap();
+        assert_eq!(hg.resolve_node(&node), Ok(n1));
+        assert_eq!(hg.resolve_edge(&edge), Ok(e0));
+        assert!(hg.node_handle(7).is_none());
+
+        let mut other = HGraph::<u8, ()>::new();
+        other.add_nodes(2);
+        assert!(other.resolve_node(&node).is_err());
+        assert!(hg.clone().resolve_node(&node).is_err());
+
+        hg.remove_node(n1);
+        assert!(hg.resolve_node(&node).is_err());
+        hg.remove_edge(e0);
+        assert!(hg.resolve_edge(&edge).is_err());
+        let n2 = hg.add_node(2);
+        let fresh = hg.node_handle(n2).unwrap();
+        hg.bump_generation();
+        assert!(hg.resolve_node(&fresh).is_err());
+        assert_eq!(hg.resolve_node(&hg.node_handle(n2).unwrap()), Ok(n2));
+    }
+
+    #[test]
+    fn filtered_graph_keeps_id_counters() {
+        let mut hg = HGraph::<(), ()>::new();
+        hg.add_nodes(4);
+        let e0 = hg.add_edge([0, 1], ());
+        hg.add_edge([2, 3], ());
+        let mut filtered = hg.filter_by_edge(|id| id == e0);
+        let mut twice = filtered.filter_by_edge(|_| true);
+        assert_eq!(filtered.add_node(()), 4);
+        assert_eq!(twice.add_node(()), 4);
+        assert_eq!(twice.add_edge([0, 1, 4], ()), 2);
+        assert_ne!(filtered.graph_tag(), hg.graph_tag());
+        assert_ne!(twice.graph_tag(), filtered.graph_tag());
+    }
+}
@@ -1,14 +1,21 @@
+use std::borrow::Borrow;
 use std::collections::HashSet;
 use std::fmt::Display;
 use std::fs::File;
 use std::io::{BufReader, Write};
 use std::path::Path;
 
-use fxhash::{FxHashMap, FxHashSet};
+use fxhash::FxHashSet;
 use serde::{Deserialize, Serialize};
 
-use crate::{ConGraph, HgNode};
-use crate::{EdgeSet, HyperGraph};
+use crate::events::Observers;
+use crate::handle::InstanceTag;
+use crate::id_map::IdMap;
+use crate::id_policy::IdAllocator;
+use crate::set_trie::SetTrie;
+use crate::similarity::SimilarityIndex;
+use crate::{ConGraph, GraphEvent, HgNode};
+use crate::{EdgeSet, HyperGraph, HyperGraphMut};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub(crate) struct Node<NodeData, EdgeID: HgNode> {
@@ -34,7 +41,7 @@ pub(crate) struct Edge<N: HgNode, EdgeData> {
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 /// An undirected hypergraph structure that is generic over structs stored
-/// for nodes and edges, as well as the ID types used for both (with defaults of `u32` and `u64`). Does not allow for duplicate edges and panics if the data type used for either type of IDs runs out of options. IDs are simple counters and IDs cannot be reused if the node or edge is deleted.
+/// for nodes and edges, as well as the ID types used for both (with defaults of `u32` and `u64`). Does not allow for duplicate edges and panics if the data type used for either type of IDs runs out of options. IDs are simple counters and are not reused once the node or edge is deleted, unless a reuse [`IdPolicy`](crate::IdPolicy) is set.
 ///
 /// Nodes are added with `add_node(data)` and edges with `add_edge(node_slice, data)` and removed similarly. Data of a node or edge can be accessed with the
 /// `borrow_node`, `borrow_edge` functions and their mutable variants. If you forget the id associated with a collection of nodes you can query the `HGraph`
@@ -46,23 +53,41 @@ pub(crate) struct Edge<N: HgNode, EdgeData> {
 /// as Gudhi. On my first evaluation it did not seem particularly beneficial
 /// asymptotically for computing links, but it may be worth investigating.
 pub struct HGraph<NodeData, EdgeData, NodeID: HgNode = u32, EdgeID: HgNode = u64> {
-    next_node_id: NodeID,
-    next_edge_id: EdgeID,
-    pub(crate) edges: FxHashMap<EdgeID, Edge<NodeID, EdgeData>>,
-    pub(crate) nodes: FxHashMap<NodeID, Node<NodeData, EdgeID>>,
+    pub(crate) next_node_id: NodeID,
+    pub(crate) next_edge_id: EdgeID,
+    pub(crate) edges: IdMap<EdgeID, Edge<NodeID, EdgeData>>,
+    pub(crate) nodes: IdMap<NodeID, Node<NodeData, EdgeID>>,
+    #[serde(skip, default = "Option::default")]
+    pub(crate) similarity: Option<SimilarityIndex<EdgeID>>,
+    #[serde(skip, default = "Option::default")]
+    pub(crate) inclusion: Option<SetTrie<NodeID, EdgeID>>,
+    #[serde(skip, default = "Option::default")]
+    pub(crate) facets: Option<FxHashSet<EdgeID>>,
+    #[serde(default = "IdAllocator::default")]
+    pub(crate) node_alloc: IdAllocator<NodeID>,
+    #[serde(default = "IdAllocator::default")]
+    pub(crate) edge_alloc: IdAllocator<EdgeID>,
+    #[serde(skip)]
+    pub(crate) tag: InstanceTag,
+    #[serde(skip)]
+    pub(crate) generation: u32,
+    #[serde(skip, default = "Observers::default")]
+    pub(crate) observers: Observers<NodeID, EdgeID>,
 }
 
-impl<NodeData, EdgeData> HGraph<NodeData, EdgeData> {
+impl<NodeData, EdgeData, NodeID: HgNode, EdgeID: HgNode>
+    HGraph<NodeData, EdgeData, NodeID, EdgeID>
+{
     /// If you have a `ConGraph` and data for each node and edge you can
-    /// build a `HGraph`.
+    /// build a `HGraph` with the same ID types.
     pub fn from_congraph<NodeFn, EdgeFn>(
-        cgraph: ConGraph,
+        cgraph: ConGraph<NodeID, EdgeID>,
         node_data: NodeFn,
         edge_data: EdgeFn,
     ) -> Self
     where
-        NodeFn: Fn(&u32) -> NodeData,
-        EdgeFn: Fn(&u64) -> EdgeData,
+        NodeFn: Fn(&NodeID) -> NodeData,
+        EdgeFn: Fn(&EdgeID) -> EdgeData,
     {
         let next_node_id = cgraph.core.next_node_id;
         let next_edge_id = cgraph.core.next_edge_id;
@@ -99,6 +124,14 @@ impl<NodeData, EdgeData> HGraph<NodeData, EdgeData> {
             next_edge_id,
             edges,
             nodes,
+            node_alloc: cgraph.core.node_alloc,
+            edge_alloc: cgraph.core.edge_alloc,
+            similarity: None,
+            inclusion: None,
+            facets: None,
+            tag: InstanceTag::new(),
+            generation: 0,
+            observers: Observers::default(),
         }
     }
 }
@@ -121,19 +154,33 @@ impl<NodeData, EdgeData, NodeID: HgNode, EdgeID: HgNode>
         Self {
             next_node_id: NodeID::zero(),
             next_edge_id: EdgeID::zero(),
-            edges: FxHashMap::default(),
-            nodes: FxHashMap::default(),
+            edges: IdMap::default(),
+            nodes: IdMap::default(),
+            node_alloc: IdAllocator::default(),
+            edge_alloc: IdAllocator::default(),
+            similarity: None,
+            inclusion: None,
+            facets: None,
+            tag: InstanceTag::new(),
+            generation: 0,
+            observers: Observers::default(),
         }
     }
 
     /// Returns the new id if a node can be added, `panic`s if the graph
     /// is out of space to add new nodes.
     pub fn add_node(&mut self, node: NodeData) -> NodeID {
-        let node_id = self.next_node_id;
-        if self.next_node_id == NodeID::max_number() {
-            panic!("The storage type for NodeIDs ran out of space.")
-        }
-        self.next_node_id.plus_one();
+        let node_id = match self.node_alloc.take_free() {
+            Some(id) => id,
+            None => {
+                let id = self.next_node_id;
+                if self.next_node_id == NodeID::max_number() {
+                    panic!("The storage type for NodeIDs ran out of space.")
+                }
+                self.next_node_id.plus_one();
+                id
+            }
+        };
 
         let new_node = Node {
             containing_edges: FxHashSet::default(),
@@ -143,6 +190,7 @@ impl<NodeData, EdgeData, NodeID: HgNode, EdgeID: HgNode>
         if insert.is_some() {
             panic!("For some reason we encountered the same node_id twice.")
         }
+        self.emit(GraphEvent::NodeAdded(node_id));
         node_id
     }
 
@@ -159,9 +207,11 @@ impl<NodeData, EdgeData, NodeID: HgNode, EdgeID: HgNode>
             return;
         }
         let mut node1_d = self.nodes.remove(node1).unwrap();
+        self.node_alloc.release(*node1);
         let mut new_edges = HashSet::new();
         let mut edge_to_remove = Vec::new();
         for edge in node1_d.containing_edges.drain() {
+            self.unindex_edge(edge);
             let e = self.edges.get_mut(&edge).unwrap();
             e.nodes.remove_node(node1);
             e.nodes.add_node(*node2);
@@ -169,11 +219,15 @@ impl<NodeData, EdgeData, NodeID: HgNode, EdgeID: HgNode>
                 edge_to_remove.push(edge);
             }
             new_edges.insert(edge);
+            self.emit(GraphEvent::EdgeNodesChanged(edge));
         }
         let node2_ref = self.nodes.get_mut(node2).unwrap();
         for e in new_edges.iter() {
             node2_ref.containing_edges.insert(*e);
         }
+        for e in new_edges.iter() {
+            self.index_edge(*e);
+        }
         for e in edge_to_remove {
             self.remove_edge(e);
         }
@@ -203,6 +257,10 @@ impl<NodeData, EdgeData, NodeID: HgNode, EdgeID: HgNode>
         for edge in duplicate_edges {
             self.remove_edge(edge);
         }
+        self.emit(GraphEvent::NodesConcatenated {
+            removed: *node1,
+            into: *node2,
+        });
     }
 
     /// Creates an edge in the hypergraph, if the edge already exists it will
@@ -221,23 +279,30 @@ impl<NodeData, EdgeData, NodeID: HgNode, EdgeID: HgNode>
                     data: data,
                 },
             );
+            self.emit(GraphEvent::EdgeDataChanged(id));
             return id;
         }
 
-        let id = self.next_edge_id;
-        // Note this technically means we can't use all possible edges
-        // but missing 1 out of the 2^64 - 1 possibilities ain't bad.
-        if self.next_edge_id == EdgeID::max_number() {
-            panic!("Ran out of edges, need to use a bigger EdgeID representation.")
-        }
-        self.next_edge_id.plus_one();
-
         let nodes = edge_set.node_vec();
         for node in nodes.iter() {
             if self.nodes.contains_key(&node) == false {
                 panic!("Adding edge but a provided node is not present in the hypergraph.")
             }
         }
+
+        let id = match self.edge_alloc.take_free() {
+            Some(id) => id,
+            None => {
+                let id = self.next_edge_id;
+                // Note this technically means we can't use all possible edges
+                // but missing 1 out of the 2^64 - 1 possibilities ain't bad.
+                if self.next_edge_id == EdgeID::max_number() {
+                    panic!("Ran out of edges, need to use a bigger EdgeID representation.")
+                }
+                self.next_edge_id.plus_one();
+                id
+            }
+        };
         for node in nodes.iter() {
             let node_link = self
                 .nodes
@@ -250,6 +315,8 @@ impl<NodeData, EdgeData, NodeID: HgNode, EdgeID: HgNode>
             data,
         };
         self.edges.insert(id.clone(), edge);
+        self.index_edge(id);
+        self.emit(GraphEvent::EdgeAdded(id));
         id
     }
 
@@ -261,9 +328,15 @@ impl<NodeData, EdgeData, NodeID: HgNode, EdgeID: HgNode>
             containing_edges: FxHashSet::default(),
             data: node,
         };
-        self.nodes
+        let old_data = self
+            .nodes
             .insert(id, new_node)
-            .map(|old_node| old_node.data)
+            .map(|old_node| old_node.data);
+        match old_data {
+            Some(_) => self.emit(GraphEvent::NodeDataChanged(id)),
+            None => self.emit(GraphEvent::NodeAdded(id)),
+        }
+        old_data
     }
 
     /// For `KVGraph` only.
@@ -299,21 +372,26 @@ impl<NodeData, EdgeData, NodeID: HgNode, EdgeID: HgNode>
             nodes: edge_set,
             data,
         };
-        self.edges
-            .insert(id.clone(), edge)
-            .map(|edge_struct| edge_struct.data)
+        let old_edge = self.edges.insert(id, edge);
+        self.index_edge(id);
+        self.emit(GraphEvent::EdgeAdded(id));
+        old_edge.map(|edge_struct| edge_struct.data)
     }
 
     /// This will remove the node from the graph and any edges containing it.
-    /// The node will not be reused in the future. If this leaves an edge
-    /// empty the edge will be removed from the graph.
+    /// The node ID is only reused later under
+    /// [`IdPolicy::ReuseFifo`](crate::IdPolicy::ReuseFifo) or
+    /// [`IdPolicy::ReuseLifo`](crate::IdPolicy::ReuseLifo). If this leaves an
+    /// edge empty the edge will be removed from the graph.
     pub fn remove_node(&mut self, node: NodeID) -> Option<NodeData> {
         if self.nodes.contains_key(&node) == false {
             return None;
         }
         let removed_node = self.nodes.remove(&node).unwrap();
+        self.node_alloc.release(node);
         let mut edges_to_be_removed = Vec::new();
         for effected_edge_id in removed_node.containing_edges.iter() {
+            self.unindex_edge(*effected_edge_id);
             let effected_edge = self
                 .edges
                 .get_mut(&effected_edge_id)
@@ -321,28 +399,65 @@ impl<NodeData, EdgeData, NodeID: HgNode, EdgeID: HgNode>
             effected_edge.nodes.remove_node(&node);
             if effected_edge.nodes.len() == 0 {
                 edges_to_be_removed.push(effected_edge_id.clone());
+            } else {
+                self.index_edge(*effected_edge_id);
+                self.emit(GraphEvent::EdgeNodesChanged(*effected_edge_id));
             }
         }
         for edge_id in edges_to_be_removed {
             self.remove_edge(edge_id);
         }
+        self.emit(GraphEvent::NodeRemoved(node));
         Some(removed_node.data)
     }
 
     /// Returns the `EdgeData` of the associated edge if it existed and `None`
     /// if an incorrect edge was provided.
     pub fn remove_edge(&mut self, edge_id: EdgeID) -> Option<EdgeData> {
+        self.unindex_edge(edge_id);
         if let Some(e) = self.edges.remove(&edge_id) {
+            self.edge_alloc.release(edge_id);
             for node in e.nodes.0.iter() {
                 let containing_edges = self.nodes.get_mut(node).expect("Why is edge not in here.");
                 containing_edges.containing_edges.remove(&edge_id);
             }
+            self.emit(GraphEvent::EdgeRemoved(edge_id));
             Some(e.data)
         } else {
             None
         }
     }
 
+    /// Adds the current nodes of `edge_id` to the optional edge indexes.
+    pub(crate) fn index_edge(&mut self, edge_id: EdgeID) {
+        let Some(edge) = self.edges.get(&edge_id) else {
+            return;
+        };
+        if let Some(index) = self.similarity.as_mut() {
+            index.insert(edge_id, &edge.nodes);
+        }
+        if let Some(index) = self.inclusion.as_mut() {
+            index.insert(edge_id, &edge.nodes);
+        }
+        self.track_added_facet(edge_id);
+    }
+
+    /// Drops `edge_id` from the optional edge indexes, has to be called
+    /// before the nodes of the edge are changed.
+    pub(crate) fn unindex_edge(&mut self, edge_id: EdgeID) {
+        if !self.edges.contains_key(&edge_id) {
+            return;
+        }
+        self.track_removed_facet(edge_id);
+        let edge = self.edges.get(&edge_id).unwrap();
+        if let Some(index) = self.similarity.as_mut() {
+            index.remove(&edge_id);
+        }
+        if let Some(index) = self.inclusion.as_mut() {
+            index.remove(&edge_id, &edge.nodes);
+        }
+    }
+
     pub fn num_nodes(&self) -> usize {
         self.nodes.len()
     }
@@ -368,6 +483,7 @@ impl<NodeData, EdgeData, NodeID: HgNode, EdgeID: HgNode>
                 data: new_data,
             };
             self.nodes.insert(node.clone(), new_node);
+            self.emit(GraphEvent::NodeDataChanged(*node));
             Some(old_node.data)
         } else {
             None
@@ -383,6 +499,7 @@ impl<NodeData, EdgeData, NodeID: HgNode, EdgeID: HgNode>
                 data: new_data,
             };
             self.edges.insert(edge_id.clone(), new_edge);
+            self.emit(GraphEvent::EdgeDataChanged(*edge_id));
             Some(old_edge.data)
         } else {
             None
@@ -394,8 +511,12 @@ impl<NodeData, EdgeData, NodeID: HgNode, EdgeID: HgNode>
         self.nodes.get(node).map(|big_node| &big_node.data)
     }
 
-    /// Borrows the data mutably of the provided node.
+    /// Borrows the data mutably of the provided node. Subscribers are told
+    /// the data changed.
     pub fn get_node_mut(&mut self, node: &NodeID) -> Option<&mut NodeData> {
+        if self.nodes.contains_key(node) {
+            self.emit(GraphEvent::NodeDataChanged(*node));
+        }
         self.nodes.get_mut(node).map(|big_node| &mut big_node.data)
     }
 
@@ -404,8 +525,12 @@ impl<NodeData, EdgeData, NodeID: HgNode, EdgeID: HgNode>
         self.edges.get(edge).map(|big_edge| &big_edge.data)
     }
 
-    /// Borrows the data mutably of the provided edge.
+    /// Borrows the data mutably of the provided edge. Subscribers are told
+    /// the data changed.
     pub fn get_edge_mut(&mut self, edge: &EdgeID) -> Option<&mut EdgeData> {
+        if self.edges.contains_key(edge) {
+            self.emit(GraphEvent::EdgeDataChanged(*edge));
+        }
         self.edges.get_mut(edge).map(|big_edge| &mut big_edge.data)
     }
 
@@ -449,47 +574,69 @@ where
     where
         F: Fn(EdgeID) -> bool,
     {
-        let new_edges: FxHashMap<EdgeID, Edge<NodeID, EdgeData>> = self
-            .edges
-            .iter()
-            .filter_map(|x| {
-                if filter(*x.0) {
-                    Some((x.0.clone(), x.1.clone()))
-                } else {
-                    None
-                }
-            })
-            .collect();
+        let new_edges = self.edges.iter().filter_map(|x| {
+            if filter(*x.0) {
+                Some((*x.0, x.1.clone()))
+            } else {
+                None
+            }
+        });
+        self.with_edges(new_edges)
+    }
+
+    /// Builds a hypergraph from a subset of this one's edges, bringing along
+    /// the nodes they need and keeping the storage mode and whichever indices
+    /// are enabled.
+    pub(crate) fn with_edges(
+        &self,
+        edges: impl IntoIterator<Item = (EdgeID, Edge<NodeID, EdgeData>)>,
+    ) -> HGraph<NodeData, EdgeData, NodeID, EdgeID> {
+        let mut new_edges = self.edges.new_like();
+        new_edges.extend(edges);
         let mut nodes_contained_in_edge = HashSet::new();
         for edge in new_edges.iter() {
             for node in edge.1.nodes.0.iter() {
                 nodes_contained_in_edge.insert(*node);
             }
         }
-        let new_nodes: FxHashMap<NodeID, Node<NodeData, EdgeID>> = nodes_contained_in_edge
-            .into_iter()
-            .map(|node| {
-                let mut new_node = self.nodes.get(&node).cloned().unwrap();
-                let new_node_edges = new_node
-                    .containing_edges
-                    .iter()
-                    .filter(|edge_id| filter(**edge_id))
-                    .cloned()
-                    .collect();
-                new_node.containing_edges = new_node_edges;
-                (node, new_node)
-            })
-            .collect();
-        let mut next_node_id = *new_nodes.keys().max().unwrap();
-        next_node_id.plus_one();
-        let mut next_edge_id = *new_edges.keys().max().unwrap();
-        next_edge_id.plus_one();
-        HGraph {
-            next_node_id,
-            next_edge_id,
+        let mut new_nodes = self.nodes.new_like();
+        new_nodes.extend(nodes_contained_in_edge.into_iter().map(|node| {
+            let mut new_node = self.nodes.get(&node).cloned().unwrap();
+            let new_node_edges = new_node
+                .containing_edges
+                .iter()
+                .filter(|edge_id| new_edges.contains_key(*edge_id))
+                .cloned()
+                .collect();
+            new_node.containing_edges = new_node_edges;
+            (node, new_node)
+        }));
+        // The counters are kept so the copy never hands out an ID the parent
+        // already used.
+        let mut filtered = HGraph {
+            next_node_id: self.next_node_id,
+            next_edge_id: self.next_edge_id,
             edges: new_edges,
             nodes: new_nodes,
+            node_alloc: self.node_alloc.clone(),
+            edge_alloc: self.edge_alloc.clone(),
+            similarity: None,
+            inclusion: None,
+            facets: None,
+            tag: InstanceTag::new(),
+            generation: 0,
+            observers: Observers::default(),
+        };
+        if let Some(index) = &self.similarity {
+            filtered.enable_similarity_index(index.bands, index.rows);
+        }
+        if self.inclusion.is_some() {
+            filtered.enable_inclusion_index();
+        }
+        if self.facets.is_some() {
+            filtered.enable_facet_tracking();
         }
+        filtered
     }
 
     pub fn filter_nodes<F>(&self, filter: F) -> Vec<NodeID>
@@ -536,44 +683,57 @@ where
             .map(|big_edge| big_edge.nodes.node_vec())
     }
 
+    fn node_ids(&self) -> impl Iterator<Item = Self::NodeID> + '_ {
+        self.nodes.keys().cloned()
+    }
+
+    fn edge_ids(&self) -> impl Iterator<Item = Self::EdgeID> + '_ {
+        self.edges.keys().cloned()
+    }
+
+    fn num_nodes(&self) -> usize {
+        self.nodes.len()
+    }
+
+    fn num_edges(&self) -> usize {
+        self.edges.len()
+    }
+
+    fn degree(&self, node: &Self::NodeID) -> Option<usize> {
+        self.nodes.get(node).map(|node| node.containing_edges.len())
+    }
+
+    fn edge_size(&self, edge: &Self::EdgeID) -> Option<usize> {
+        self.edges.get(edge).map(|edge| edge.nodes.len())
+    }
+
+    fn incident_edges(&self, node: &Self::NodeID) -> Vec<Self::EdgeID> {
+        self.nodes
+            .get(node)
+            .map(|node| node.containing_edges.iter().cloned().collect())
+            .unwrap_or_default()
+    }
+
     fn containing_edges_of_nodes(&self, nodes: impl AsRef<[Self::NodeID]>) -> Vec<Self::EdgeID> {
         let nodes_set: EdgeSet<Self::NodeID> = nodes.into();
-        let first = nodes_set.get_first_node().unwrap();
-        if self.nodes.contains_key(&first) == false {
-            return vec![];
-        }
-        let candidate_ids = self.nodes.get(&first).unwrap();
-        let mut ret = Vec::new();
-        for candidate_id in candidate_ids.containing_edges.iter() {
-            let candidate = self
-                .edges
-                .get(candidate_id)
-                .expect("Edge invariant violated.");
-            if candidate.nodes.contains_strict(&nodes_set) {
-                ret.push(candidate_id.clone());
-            }
-        }
-        ret
+        self.supersets_of_nodes(&nodes_set.0)
+            .into_iter()
+            .filter(|id| {
+                self.edges
+                    .get(id)
+                    .expect("Edge invariant violated.")
+                    .nodes
+                    .len()
+                    > nodes_set.len()
+            })
+            .collect()
     }
 
     fn containing_edges(&self, edge: &Self::EdgeID) -> Vec<Self::EdgeID> {
-        if self.edges.contains_key(edge) == false {
-            return Vec::new();
-        }
-        let edge = self.edges.get(edge).unwrap();
-        let first = edge.nodes.get_first_node().unwrap();
-        let candidate_ids = self.nodes.get(&first).unwrap();
-        let mut ret = Vec::new();
-        for candidate_id in candidate_ids.containing_edges.iter() {
-            let candidate = self
-                .edges
-                .get(candidate_id)
-                .expect("Edge invariant violated.");
-            if candidate.nodes.contains_strict(&edge.nodes) {
-                ret.push(candidate_id.clone());
-            }
+        match self.edges.get(edge) {
+            Some(edge) => self.containing_edges_of_nodes(&edge.nodes.0),
+            None => Vec::new(),
         }
-        ret
     }
 
     fn link(&self, edge: &Self::EdgeID) -> Vec<(Self::EdgeID, Vec<Self::NodeID>)> {
@@ -625,73 +785,22 @@ where
     }
 
     fn maximal_edges(&self, edge_id: &Self::EdgeID) -> Vec<Self::EdgeID> {
-        let containing_edges = self.containing_edges(edge_id);
-        if containing_edges.is_empty() {
-            return Vec::new();
-        }
-        let mut submaximal_edges = HashSet::new();
-        for ix in 0..containing_edges.len() {
-            if submaximal_edges.contains(&containing_edges[ix]) {
-                continue;
-            }
-            let edge_ix = self
-                .edges
-                .get(&containing_edges[ix])
-                .expect("Edge invariant broken.");
-            for jx in 0..containing_edges.len() {
-                if ix == jx {
-                    continue;
-                }
-                let edge_jx = self
-                    .edges
-                    .get(&containing_edges[jx])
-                    .expect("Edge invariant broken.");
-                if edge_jx.nodes.contains_strict(&edge_ix.nodes) {
-                    submaximal_edges.insert(containing_edges[ix].clone());
-                } else if edge_ix.nodes.contains_strict(&edge_jx.nodes) {
-                    submaximal_edges.insert(containing_edges[jx].clone());
-                }
-            }
+        match self.edges.get(edge_id) {
+            Some(edge) => self.maximal_edges_of_nodes(&edge.nodes.0),
+            None => Vec::new(),
         }
-        containing_edges
-            .into_iter()
-            .filter(|id| submaximal_edges.contains(id) == false)
-            .collect()
     }
 
     fn maximal_edges_of_nodes(&self, nodes: impl AsRef<[Self::NodeID]>) -> Vec<Self::EdgeID> {
-        let containing_edges = self.containing_edges_of_nodes(nodes);
-        if containing_edges.is_empty() {
-            return Vec::new();
-        }
-        let mut submaximal_edges = HashSet::new();
-        for ix in 0..containing_edges.len() {
-            if submaximal_edges.contains(&containing_edges[ix]) {
-                continue;
-            }
-            let edge_ix = self
-                .edges
-                .get(&containing_edges[ix])
-                .expect("Edge invariant broken.");
-            let mut is_edge_ix_maximal = true;
-            for jx in 0..containing_edges.len() {
-                if ix == jx {
-                    continue;
-                }
-                let edge_jx = self
-                    .edges
-                    .get(&containing_edges[jx])
-                    .expect("Edge invariant broken.");
-                if edge_jx.nodes.contains_strict(&edge_ix.nodes) {
-                    is_edge_ix_maximal = false;
-                    break;
-                }
-            }
-            if is_edge_ix_maximal {
-                submaximal_edges.insert(containing_edges[ix]);
-            }
-        }
-        submaximal_edges.into_iter().collect()
+        // A containing edge is maximal exactly when nothing strictly contains
+        // it, as anything that did would also contain the input.
+        self.containing_edges_of_nodes(nodes)
+            .into_iter()
+            .filter(|id| {
+                let edge = self.edges.get(id).expect("Edge invariant broken.");
+                !self.has_strict_superset(&edge.nodes)
+            })
+            .collect()
     }
 
     fn edges_of_size(&self, card: usize) -> Vec<Self::EdgeID> {
@@ -801,6 +910,261 @@ where
             .map(|(id, _)| id.clone())
             .collect()
     }
+
+    fn containing_edges_iter(
+        &self,
+        edge: &Self::EdgeID,
+    ) -> impl Iterator<Item = Self::EdgeID> + '_ {
+        self.strict_supersets_iter(self.edges.get(edge).map(|edge| &edge.nodes))
+    }
+
+    fn containing_edges_of_nodes_iter(
+        &self,
+        nodes: impl AsRef<[Self::NodeID]>,
+    ) -> impl Iterator<Item = Self::EdgeID> + '_ {
+        self.strict_supersets_iter(Some(EdgeSet::from(nodes.as_ref())))
+    }
+
+    fn link_iter(
+        &self,
+        edge: &Self::EdgeID,
+    ) -> impl Iterator<Item = (Self::EdgeID, Vec<Self::NodeID>)> + '_ {
+        let nodes = self.edges.get(edge).map(|edge| &edge.nodes);
+        self.strict_supersets_iter(nodes).filter_map(move |id| {
+            let link = self.edges.get(&id).unwrap().nodes.link(nodes.unwrap())?;
+            Some((id, link.to_node_vec()))
+        })
+    }
+
+    fn link_of_nodes_iter(
+        &self,
+        nodes: impl AsRef<[Self::NodeID]>,
+    ) -> impl Iterator<Item = (Self::EdgeID, Vec<Self::NodeID>)> + '_ {
+        let nodes: EdgeSet<Self::NodeID> = nodes.into();
+        self.strict_supersets_iter(Some(nodes.clone()))
+            .filter_map(move |id| {
+                let link = self.edges.get(&id).unwrap().nodes.link(&nodes)?;
+                Some((id, link.to_node_vec()))
+            })
+    }
+
+    fn maximal_edges_iter(
+        &self,
+        edge_id: &Self::EdgeID,
+    ) -> impl Iterator<Item = Self::EdgeID> + '_ {
+        self.containing_edges_iter(edge_id)
+            .filter(|id| !self.has_strict_superset(&self.edges.get(id).unwrap().nodes))
+    }
+
+    fn maximal_edges_of_nodes_iter(
+        &self,
+        nodes: impl AsRef<[Self::NodeID]>,
+    ) -> impl Iterator<Item = Self::EdgeID> + '_ {
+        self.strict_supersets_iter(Some(EdgeSet::from(nodes.as_ref())))
+            .filter(|id| !self.has_strict_superset(&self.edges.get(id).unwrap().nodes))
+    }
+
+    fn edges_of_size_iter(&self, card: usize) -> impl Iterator<Item = Self::EdgeID> + '_ {
+        self.edges
+            .iter()
+            .filter(move |(_, e)| e.nodes.len() == card)
+            .map(|(id, _)| *id)
+    }
+
+    fn skeleton_iter(&self, cardinality: usize) -> impl Iterator<Item = Self::EdgeID> + '_ {
+        self.edges
+            .iter()
+            .filter(move |(_, e)| e.nodes.len() <= cardinality)
+            .map(|(id, _)| *id)
+    }
+
+    fn boundary_up_ids_iter(
+        &self,
+        edge_id: &Self::EdgeID,
+    ) -> impl Iterator<Item = Self::EdgeID> + '_ {
+        let size = self.edges.get(edge_id).map_or(0, |edge| edge.nodes.len());
+        self.containing_edges_iter(edge_id)
+            .filter(move |id| self.edges.get(id).unwrap().nodes.len() == size + 1)
+    }
+
+    fn boundary_down_ids_iter(
+        &self,
+        edge_id: &Self::EdgeID,
+    ) -> impl Iterator<Item = Self::EdgeID> + '_ {
+        self.boundary_down_ids_iter_of(self.edges.get(edge_id).map(|edge| &edge.nodes))
+    }
+
+    fn boundary_up_of_nodes_ids_iter(
+        &self,
+        nodes: impl AsRef<[Self::NodeID]>,
+    ) -> impl Iterator<Item = Self::EdgeID> + '_ {
+        let nodes = EdgeSet::from(nodes.as_ref());
+        let size = nodes.len();
+        self.strict_supersets_iter(Some(nodes))
+            .filter(move |id| self.edges.get(id).unwrap().nodes.len() == size + 1)
+    }
+
+    fn boundary_down_of_nodes_ids_iter(
+        &self,
+        nodes: impl AsRef<[Self::NodeID]>,
+    ) -> impl Iterator<Item = Self::EdgeID> + '_ {
+        self.boundary_down_ids_iter_of(Some(EdgeSet::from(nodes.as_ref())))
+    }
+
+    fn faces_of_nodes(&self, nodes: impl AsRef<[Self::NodeID]>) -> Vec<Self::EdgeID> {
+        let nodes_set: EdgeSet<Self::NodeID> = nodes.into();
+        self.subsets_of_nodes(&nodes_set.0)
+            .into_iter()
+            .filter(|id| {
+                self.edges
+                    .get(id)
+                    .expect("Edge invariant violated.")
+                    .nodes
+                    .len()
+                    < nodes_set.len()
+            })
+            .collect()
+    }
+
+    fn deletion(&self, edge_id: &Self::EdgeID) -> Vec<Self::EdgeID> {
+        let Some(edge) = self.edges.get(edge_id) else {
+            return Vec::new();
+        };
+        self.edges
+            .iter()
+            .filter(|(_, e)| !e.nodes.contains(&edge.nodes))
+            .map(|(id, _)| *id)
+            .collect()
+    }
+}
+
+impl<NodeData, EdgeData, NodeID: HgNode, EdgeID: HgNode>
+    HGraph<NodeData, EdgeData, NodeID, EdgeID>
+{
+    /// Lazily yields the edges strictly containing `nodes` by walking the
+    /// containing edges of the node in the fewest edges. Yields nothing if
+    /// `nodes` is `None`.
+    fn strict_supersets_iter<'a, S>(&'a self, nodes: Option<S>) -> impl Iterator<Item = EdgeID> + 'a
+    where
+        S: Borrow<EdgeSet<NodeID>> + 'a,
+    {
+        let mut rarest: Option<&FxHashSet<EdgeID>> = None;
+        let mut all_edges = None;
+        if let Some(query) = &nodes {
+            let query = query.borrow();
+            if query.is_empty() {
+                all_edges = Some(self.edges.keys());
+            }
+            for node in query.0.iter() {
+                match self.nodes.get(node) {
+                    Some(node) => {
+                        if rarest.is_none_or(|edges| node.containing_edges.len() < edges.len()) {
+                            rarest = Some(&node.containing_edges);
+                        }
+                    }
+                    None => {
+                        rarest = None;
+                        break;
+                    }
+                }
+            }
+        }
+        rarest
+            .into_iter()
+            .flatten()
+            .chain(all_edges.into_iter().flatten())
+            .cloned()
+            .filter(move |id| {
+                let query = nodes.as_ref().unwrap().borrow();
+                self.edges.get(id).unwrap().nodes.contains_strict(query)
+            })
+    }
+
+    /// Lazily yields the edges that are `nodes` with one node removed. Such
+    /// an edge contains the first or second node, so only their containing
+    /// edges are walked.
+    fn boundary_down_ids_iter_of<'a, S>(
+        &'a self,
+        nodes: Option<S>,
+    ) -> impl Iterator<Item = EdgeID> + 'a
+    where
+        S: Borrow<EdgeSet<NodeID>> + 'a,
+    {
+        let mut first = None;
+        let mut second = None;
+        if let Some(query) = &nodes {
+            let query = query.borrow();
+            if query.len() >= 2 {
+                first = self
+                    .nodes
+                    .get(&query.0[0])
+                    .map(|node| node.containing_edges.iter());
+                second = self
+                    .nodes
+                    .get(&query.0[1])
+                    .map(|node| node.containing_edges.iter());
+            }
+        }
+        let from_first = first.into_iter().flatten().map(|id| (id, true));
+        let from_second = second.into_iter().flatten().map(|id| (id, false));
+        from_first
+            .chain(from_second)
+            .filter(move |(id, is_first)| {
+                let query = nodes.as_ref().unwrap().borrow();
+                let face = &self.edges.get(id).unwrap().nodes;
+                // Faces with both of the first two nodes show up in both
+                // containing edge sets, only take them from the first.
+                let repeated = !is_first && face.contains_node(&query.0[0]);
+                !repeated && face.len() + 1 == query.len() && query.contains(face)
+            })
+            .map(|(id, _)| *id)
+    }
+}
+
+impl<NodeData, EdgeData, NodeID, EdgeID> HyperGraphMut
+    for HGraph<NodeData, EdgeData, NodeID, EdgeID>
+where
+    NodeID: HgNode,
+    EdgeID: HgNode,
+    NodeData: Default,
+    EdgeData: Default,
+{
+    type NodeData = NodeData;
+    type EdgeData = EdgeData;
+
+    fn add_node_default(&mut self) -> Self::NodeID {
+        self.add_node(NodeData::default())
+    }
+
+    fn add_edge_default(&mut self, nodes: impl AsRef<[Self::NodeID]>) -> Self::EdgeID {
+        match self.find_id(nodes.as_ref()) {
+            Some(id) => id,
+            None => self.add_edge(nodes, EdgeData::default()),
+        }
+    }
+
+    fn remove_node(&mut self, node: Self::NodeID) -> Option<Self::NodeData> {
+        HGraph::remove_node(self, node)
+    }
+
+    fn remove_edge(&mut self, edge: Self::EdgeID) -> Option<Self::EdgeData> {
+        HGraph::remove_edge(self, edge)
+    }
+
+    fn contract(&mut self, nodes: impl AsRef<[Self::NodeID]>) -> Option<Self::NodeID> {
+        let (target, rest) = nodes.as_ref().split_first()?;
+        if nodes
+            .as_ref()
+            .iter()
+            .any(|node| !self.nodes.contains_key(node))
+        {
+            return None;
+        }
+        for node in rest {
+            self.concatenate_nodes(node, target);
+        }
+        Some(*target)
+    }
 }
 
 impl<NodeData, EdgeData, NodeID, EdgeID> HGraph<NodeData, EdgeData, NodeID, EdgeID>
@@ -872,7 +1236,7 @@ where
 
 #[cfg(test)]
 mod tests {
-    use crate::HyperGraph;
+    use crate::{sorted, HyperGraph};
 
     use super::HGraph;
 
@@ -1061,4 +1425,84 @@ mod tests {
         let test_4 = hg.boundary_down_of_nodes(vec![0, 1, 3]);
         assert_eq!(test_4, expected_4);
     }
+
+    #[test]
+    fn faces_and_stars() {
+        let mut hg = HGraph::<(), ()>::new();
+        hg.add_nodes(5);
+        let e0 = hg.add_edge([0], ());
+        let e1 = hg.add_edge([0, 1], ());
+        let e2 = hg.add_edge([1, 2], ());
+        let e3 = hg.add_edge([0, 1, 2], ());
+        let e4 = hg.add_edge([1, 2, 3], ());
+        let e5 = hg.add_edge([3, 4], ());
+        assert_eq!(sorted(hg.faces(&e3)), vec![e0, e1, e2]);
+        assert_eq!(
+            sorted(hg.faces_of_nodes([0, 1, 2, 3])),
+            vec![e0, e1, e2, e3, e4]
+        );
+        assert_eq!(sorted(hg.cofaces(&e2)), vec![e3, e4]);
+        assert_eq!(sorted(hg.open_star(&e2)), vec![e2, e3, e4]);
+        assert_eq!(sorted(hg.closed_star(&e2)), vec![e0, e1, e2, e3, e4]);
+        assert_eq!(sorted(hg.deletion(&e2)), vec![e0, e1, e5]);
+        assert!(hg.faces(&17).is_empty());
+        assert!(hg.deletion(&17).is_empty());
+        // The default method, used by the frozen snapshot, agrees.
+        let frozen = hg.freeze();
+        assert_eq!(sorted(frozen.deletion(&e2)), vec![e0, e1, e5]);
+        assert!(frozen.deletion(&17).is_empty());
+    }
+
+    #[test]
+    fn lazy_queries_match() {
+        let mut hg = HGraph::<(), ()>::new();
+        hg.add_nodes(6);
+        let e0 = hg.add_edge([0, 1], ());
+        hg.add_edge([0, 1, 2], ());
+        hg.add_edge([0, 1, 3], ());
+        let e3 = hg.add_edge([0, 1, 2, 3], ());
+        hg.add_edge([1, 2, 5], ());
+        hg.add_edge([1, 2], ());
+        for indexed in [false, true] {
+            if indexed {
+                hg.enable_inclusion_index();
+            }
+            let mut link: Vec<_> = hg.link_iter(&e0).collect();
+            link.sort();
+            let mut expected = hg.link(&e0);
+            expected.sort();
+            assert_eq!(link, expected);
+            assert_eq!(
+                sorted(hg.containing_edges_iter(&e0).collect()),
+                sorted(hg.containing_edges(&e0))
+            );
+            assert_eq!(
+                sorted(hg.containing_edges_of_nodes_iter([1, 2]).collect()),
+                sorted(hg.containing_edges_of_nodes([1, 2]))
+            );
+            assert_eq!(
+                sorted(hg.maximal_edges_of_nodes_iter([1]).collect()),
+                sorted(hg.maximal_edges_of_nodes([1]))
+            );
+            assert_eq!(hg.edges_of_size_iter(4).collect::<Vec<_>>(), vec![e3]);
+            assert_eq!(hg.skeleton_iter(2).count(), 2);
+            let up: Vec<_> = hg
+                .boundary_up_ids_iter(&e0)
+                .map(|id| hg.query_edge(&id).unwrap())
+                .collect();
+            assert_eq!(sorted(up), sorted(hg.boundary_up(&e0)));
+            let down: Vec<_> = hg
+                .boundary_down_ids_iter(&e3)
+                .map(|id| hg.query_edge(&id).unwrap())
+                .collect();
+            assert_eq!(sorted(down), sorted(hg.boundary_down(&e3)));
+            assert_eq!(
+                hg.boundary_up_of_nodes_ids_iter([0, 1, 2])
+                    .collect::<Vec<_>>(),
+                vec![e3]
+            );
+            assert_eq!(hg.boundary_down_of_nodes_ids_iter([0, 1, 2]).count(), 2);
+            assert_eq!(hg.containing_edges_iter(&17).count(), 0);
+        }
+    }
 }
@@ -1,4 +1,6 @@
-use crate::HgNode;
+use std::collections::HashSet;
+
+use crate::{EdgeSet, HgNode};
 
 /// The connectivity features of a hypergraph, used for developing algorithms
 /// in a struct independent way.
@@ -9,6 +11,32 @@ pub trait HyperGraph {
     /// Retrieve the nodes associated with the given `EdgeID`
     fn query_edge(&self, edge: &Self::EdgeID) -> Option<Vec<Self::NodeID>>;
 
+    /// Iterates over the IDs of every node, in no particular order.
+    fn node_ids(&self) -> impl Iterator<Item = Self::NodeID> + '_;
+
+    /// Iterates over the IDs of every edge, in no particular order.
+    fn edge_ids(&self) -> impl Iterator<Item = Self::EdgeID> + '_;
+
+    fn num_nodes(&self) -> usize {
+        self.node_ids().count()
+    }
+
+    fn num_edges(&self) -> usize {
+        self.edge_ids().count()
+    }
+
+    /// The number of edges containing the node, `None` if the node is not
+    /// present.
+    fn degree(&self, node: &Self::NodeID) -> Option<usize>;
+
+    /// The number of nodes in the edge, `None` if the edge is not present.
+    fn edge_size(&self, edge: &Self::EdgeID) -> Option<usize> {
+        self.query_edge(edge).map(|nodes| nodes.len())
+    }
+
+    /// The edges containing the node, empty if the node is not present.
+    fn incident_edges(&self, node: &Self::NodeID) -> Vec<Self::EdgeID>;
+
     /// Find all edge ids such that the given nodes are a subset or equal to
     /// the edge.
     fn containing_edges_of_nodes(&self, nodes: impl AsRef<[Self::NodeID]>) -> Vec<Self::EdgeID>;
@@ -69,4 +97,280 @@ pub trait HyperGraph {
 
     /// Finds all edges that have one node removed from the provided nodes.
     fn boundary_down_of_nodes(&self, nodes: impl AsRef<[Self::NodeID]>) -> Vec<Vec<Self::NodeID>>;
+
+    /// Lazy version of [`containing_edges`](HyperGraph::containing_edges).
+    /// The iterator versions of the queries borrow from the graph instead of
+    /// collecting into a `Vec`, the default implementations just wrap the
+    /// `Vec` returning methods.
+    fn containing_edges_iter(
+        &self,
+        edge: &Self::EdgeID,
+    ) -> impl Iterator<Item = Self::EdgeID> + '_ {
+        self.containing_edges(edge).into_iter()
+    }
+
+    /// Lazy version of
+    /// [`containing_edges_of_nodes`](HyperGraph::containing_edges_of_nodes).
+    fn containing_edges_of_nodes_iter(
+        &self,
+        nodes: impl AsRef<[Self::NodeID]>,
+    ) -> impl Iterator<Item = Self::EdgeID> + '_ {
+        self.containing_edges_of_nodes(nodes).into_iter()
+    }
+
+    /// Lazy version of [`link`](HyperGraph::link).
+    fn link_iter(
+        &self,
+        edge: &Self::EdgeID,
+    ) -> impl Iterator<Item = (Self::EdgeID, Vec<Self::NodeID>)> + '_ {
+        self.link(edge).into_iter()
+    }
+
+    /// Lazy version of [`link_of_nodes`](HyperGraph::link_of_nodes).
+    fn link_of_nodes_iter(
+        &self,
+        nodes: impl AsRef<[Self::NodeID]>,
+    ) -> impl Iterator<Item = (Self::EdgeID, Vec<Self::NodeID>)> + '_ {
+        self.link_of_nodes(nodes).into_iter()
+    }
+
+    /// Lazy version of [`maximal_edges`](HyperGraph::maximal_edges).
+    fn maximal_edges_iter(
+        &self,
+        edge_id: &Self::EdgeID,
+    ) -> impl Iterator<Item = Self::EdgeID> + '_ {
+        self.maximal_edges(edge_id).into_iter()
+    }
+
+    /// Lazy version of
+    /// [`maximal_edges_of_nodes`](HyperGraph::maximal_edges_of_nodes).
+    fn maximal_edges_of_nodes_iter(
+        &self,
+        nodes: impl AsRef<[Self::NodeID]>,
+    ) -> impl Iterator<Item = Self::EdgeID> + '_ {
+        self.maximal_edges_of_nodes(nodes).into_iter()
+    }
+
+    /// Lazy version of [`edges_of_size`](HyperGraph::edges_of_size).
+    fn edges_of_size_iter(&self, card: usize) -> impl Iterator<Item = Self::EdgeID> + '_ {
+        self.edges_of_size(card).into_iter()
+    }
+
+    /// Lazy version of [`skeleton`](HyperGraph::skeleton).
+    fn skeleton_iter(&self, cardinality: usize) -> impl Iterator<Item = Self::EdgeID> + '_ {
+        self.skeleton(cardinality).into_iter()
+    }
+
+    /// Lazy version of [`boundary_up`](HyperGraph::boundary_up) that yields
+    /// the IDs of the boundary edges instead of their nodes.
+    fn boundary_up_ids_iter(
+        &self,
+        edge_id: &Self::EdgeID,
+    ) -> impl Iterator<Item = Self::EdgeID> + '_ {
+        let size = self.edge_size(edge_id);
+        self.containing_edges_iter(edge_id)
+            .filter(move |id| self.edge_size(id) == size.map(|size| size + 1))
+    }
+
+    /// Lazy version of [`boundary_down`](HyperGraph::boundary_down) that
+    /// yields the IDs of the boundary edges instead of their nodes.
+    fn boundary_down_ids_iter(
+        &self,
+        edge_id: &Self::EdgeID,
+    ) -> impl Iterator<Item = Self::EdgeID> + '_ {
+        let size = self.edge_size(edge_id);
+        self.faces(edge_id)
+            .into_iter()
+            .filter(move |id| self.edge_size(id).map(|face| face + 1) == size)
+    }
+
+    /// Lazy version of
+    /// [`boundary_up_of_nodes`](HyperGraph::boundary_up_of_nodes) that yields
+    /// the IDs of the boundary edges instead of their nodes.
+    fn boundary_up_of_nodes_ids_iter(
+        &self,
+        nodes: impl AsRef<[Self::NodeID]>,
+    ) -> impl Iterator<Item = Self::EdgeID> + '_ {
+        let size = EdgeSet::from(nodes.as_ref()).len();
+        self.containing_edges_of_nodes(nodes)
+            .into_iter()
+            .filter(move |id| self.edge_size(id) == Some(size + 1))
+    }
+
+    /// Lazy version of
+    /// [`boundary_down_of_nodes`](HyperGraph::boundary_down_of_nodes) that
+    /// yields the IDs of the boundary edges instead of their nodes.
+    fn boundary_down_of_nodes_ids_iter(
+        &self,
+        nodes: impl AsRef<[Self::NodeID]>,
+    ) -> impl Iterator<Item = Self::EdgeID> + '_ {
+        let size = EdgeSet::from(nodes.as_ref()).len();
+        self.faces_of_nodes(nodes)
+            .into_iter()
+            .filter(move |id| self.edge_size(id).map(|face| face + 1) == Some(size))
+    }
+
+    /// Finds every edge that is a **strict** subset of the provided edge, at
+    /// every cardinality. Returns an empty vec if the edge_id is incorrect.
+    /// Ex: If the graph has edges {1}, {1, 2}, {2, 3} and {1, 2, 3} then the
+    /// faces of {1, 2, 3} are {1}, {1, 2} and {2, 3}.
+    fn faces(&self, edge_id: &Self::EdgeID) -> Vec<Self::EdgeID> {
+        match self.query_edge(edge_id) {
+            Some(nodes) => self.faces_of_nodes(nodes),
+            None => Vec::new(),
+        }
+    }
+
+    /// Finds every edge that is a **strict** subset of the provided nodes.
+    /// The default implementation filters all edges below the size of the
+    /// input.
+    fn faces_of_nodes(&self, nodes: impl AsRef<[Self::NodeID]>) -> Vec<Self::EdgeID> {
+        let nodes: EdgeSet<Self::NodeID> = nodes.into();
+        if nodes.is_empty() {
+            return Vec::new();
+        }
+        self.skeleton(nodes.len() - 1)
+            .into_iter()
+            .filter(|id| {
+                let face: EdgeSet<Self::NodeID> = self.query_edge(id).unwrap_or_default().into();
+                nodes.contains_strict(&face)
+            })
+            .collect()
+    }
+
+    /// Finds every edge that the provided edge is a **strict** subset of, the
+    /// same as [`containing_edges`](HyperGraph::containing_edges).
+    fn cofaces(&self, edge_id: &Self::EdgeID) -> Vec<Self::EdgeID> {
+        self.containing_edges(edge_id)
+    }
+
+    /// The open star of an edge, the edge itself along with all of its
+    /// cofaces. Returns an empty vec if the edge_id is incorrect.
+    fn open_star(&self, edge_id: &Self::EdgeID) -> Vec<Self::EdgeID> {
+        if self.query_edge(edge_id).is_none() {
+            return Vec::new();
+        }
+        let mut star = self.cofaces(edge_id);
+        star.push(*edge_id);
+        star
+    }
+
+    /// The closed star of an edge, the open star along with every face of an
+    /// edge in the open star.
+    fn closed_star(&self, edge_id: &Self::EdgeID) -> Vec<Self::EdgeID> {
+        let open_star = self.open_star(edge_id);
+        let mut star: HashSet<Self::EdgeID> = open_star.iter().cloned().collect();
+        for id in open_star.iter() {
+            star.extend(self.faces(id));
+        }
+        star.into_iter().collect()
+    }
+
+    /// The deletion of an edge, every edge that does not contain it. This is
+    /// everything except the open star, or nothing if the edge does not exist.
+    fn deletion(&self, edge_id: &Self::EdgeID) -> Vec<Self::EdgeID> {
+        if self.query_edge(edge_id).is_none() {
+            return Vec::new();
+        }
+        let open_star: HashSet<Self::EdgeID> = self.open_star(edge_id).into_iter().collect();
+        self.edge_ids()
+            .filter(|id| !open_star.contains(id))
+            .collect()
+    }
+}
+
+/// Mutation operations shared by the hypergraph structures, so that
+/// algorithms that build or modify a hypergraph can be written once. Nodes and
+/// edges created through this trait get default data.
+pub trait HyperGraphMut: HyperGraph {
+    type NodeData;
+    type EdgeData;
+
+    /// Adds a node with default data and returns its ID.
+    fn add_node_default(&mut self) -> Self::NodeID;
+
+    /// Adds an edge among the provided nodes with default data and returns its
+    /// ID. If the edge already exists its ID is returned and the data is left
+    /// alone.
+    fn add_edge_default(&mut self, nodes: impl AsRef<[Self::NodeID]>) -> Self::EdgeID;
+
+    /// Removes the node and returns its data, edges left empty are removed.
+    fn remove_node(&mut self, node: Self::NodeID) -> Option<Self::NodeData>;
+
+    /// Removes the edge and returns its data.
+    fn remove_edge(&mut self, edge: Self::EdgeID) -> Option<Self::EdgeData>;
+
+    /// Merges all of the provided nodes into the first one, which keeps its
+    /// data while the others are removed. Edges are rewritten to use the
+    /// first node, edges reduced to a single node and duplicate edges are
+    /// removed. Returns the surviving node or `None` if no nodes were given
+    /// or any of them is missing.
+    fn contract(&mut self, nodes: impl AsRef<[Self::NodeID]>) -> Option<Self::NodeID>;
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{ConGraph, HGraph, HyperGraph, HyperGraphMut};
+
+    /// Sum of degrees and sum of edge sizes agree for any hypergraph.
+    fn degree_sum<H: HyperGraph>(hg: &H) -> (usize, usize) {
+        let degrees = hg.node_ids().map(|node| hg.degree(&node).unwrap()).sum();
+        let sizes = hg.edge_ids().map(|edge| hg.edge_size(&edge).unwrap()).sum();
+        (degrees, sizes)
+    }
+
+    #[test]
+    fn enumeration_and_degrees() {
+        let mut cg = ConGraph::new();
+        let nodes = cg.add_nodes(4);
+        let e0 = cg.add_edge([nodes[0], nodes[1]]);
+        let e1 = cg.add_edge([nodes[0], nodes[1], nodes[2]]);
+        assert_eq!(HyperGraph::num_nodes(&cg), 4);
+        assert_eq!(HyperGraph::num_edges(&cg), 2);
+        assert_eq!(cg.degree(&nodes[0]), Some(2));
+        assert_eq!(cg.degree(&nodes[3]), Some(0));
+        assert_eq!(cg.degree(&17), None);
+        assert_eq!(cg.edge_size(&e1), Some(3));
+        let mut incident = cg.incident_edges(&nodes[1]);
+        incident.sort();
+        assert_eq!(incident, vec![e0, e1]);
+        assert_eq!(degree_sum(&cg), (5, 5));
+
+        let hg = HGraph::<(), (), _, _>::from_congraph(cg, |_| (), |_| ());
+        assert_eq!(degree_sum(&hg), (5, 5));
+    }
+
+    /// Builds a triangle fan through the generic mutation trait.
+    fn fan<H: HyperGraphMut>(hg: &mut H, blades: usize) -> H::NodeID {
+        let hub = hg.add_node_default();
+        let mut prev = hg.add_node_default();
+        for _ in 0..blades {
+            let next = hg.add_node_default();
+            hg.add_edge_default([hub, prev, next]);
+            prev = next;
+        }
+        hub
+    }
+
+    #[test]
+    fn generic_mutation() {
+        let mut cg = ConGraph::new();
+        let hub = fan(&mut cg, 3);
+        assert_eq!(HyperGraph::num_edges(&cg), 3);
+        assert_eq!(cg.degree(&hub), Some(3));
+
+        let mut hg = HGraph::<u8, String>::new();
+        let hub = fan(&mut hg, 3);
+        // Contracting the two outer nodes of the first blade into the hub
+        // leaves it as a single node edge, which gets dropped.
+        assert_eq!(hg.contract([hub, 1, 2]), Some(hub));
+        assert_eq!(HyperGraph::num_nodes(&hg), 3);
+        assert_eq!(HyperGraph::num_edges(&hg), 2);
+        assert_eq!(hg.contract([hub, 17]), None);
+        let edge = hg.edge_ids().next().unwrap();
+        assert_eq!(
+            HyperGraphMut::remove_edge(&mut hg, edge),
+            Some(String::new())
+        );
+    }
 }
@@ -0,0 +1,386 @@
+use std::fmt;
+use std::marker::PhantomData;
+
+use fxhash::FxHashMap;
+use serde::de::{MapAccess, Visitor};
+use serde::ser::SerializeMap;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::{HGraph, HgNode};
+
+/// Storage for the nodes or edges of an [`HGraph`] keyed by ID. `Hashed`
+/// works for any ID type while `Dense` keeps a slot per ID in a `Vec`, so
+/// lookups are direct indexing and iteration is in increasing ID order. Dense
+/// storage only suits IDs handed out by the counters, a large hand picked ID
+/// allocates every slot below it.
+#[derive(Debug, Clone)]
+pub(crate) enum IdMap<K: HgNode, V> {
+    Hashed(FxHashMap<K, V>),
+    Dense {
+        slots: Vec<Option<(K, V)>>,
+        len: usize,
+    },
+}
+
+/// Either of the two storage iterators, so the methods below can return a
+/// single type.
+pub(crate) enum Either<A, B> {
+    Hashed(A),
+    Dense(B),
+}
+
+impl<A: Iterator, B: Iterator<Item = A::Item>> Iterator for Either<A, B> {
+    type Item = A::Item;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self {
+            Either::Hashed(iter) => iter.next(),
+            Either::Dense(iter) => iter.next(),
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        match self {
+            Either::Hashed(iter) => iter.size_hint(),
+            Either::Dense(iter) => iter.size_hint(),
+        }
+    }
+}
+
+fn slot<K: HgNode>(key: &K) -> usize {
+    key.dense_index()
+        .expect("ID type cannot be used with dense storage.")
+}
+
+impl<K: HgNode, V> Default for IdMap<K, V> {
+    fn default() -> Self {
+        IdMap::Hashed(FxHashMap::default())
+    }
+}
+
+impl<K: HgNode, V> IdMap<K, V> {
+    pub(crate) fn new_dense() -> Self {
+        IdMap::Dense {
+            slots: Vec::new(),
+            len: 0,
+        }
+    }
+
+    /// An empty map using the same storage as `self`.
+    pub(crate) fn new_like(&self) -> Self {
+        match self {
+            IdMap::Hashed(_) => IdMap::default(),
+            IdMap::Dense { .. } => IdMap::new_dense(),
+        }
+    }
+
+    pub(crate) fn is_dense(&self) -> bool {
+        matches!(self, IdMap::Dense { .. })
+    }
+
+    pub(crate) fn into_dense(self) -> Self {
+        let mut dense = IdMap::new_dense();
+        dense.extend(self);
+        dense
+    }
+
+    pub(crate) fn into_hashed(self) -> Self {
+        let mut hashed = IdMap::default();
+        hashed.extend(self);
+        hashed
+    }
+
+    pub(crate) fn len(&self) -> usize {
+        match self {
+            IdMap::Hashed(map) => map.len(),
+            IdMap::Dense { len, .. } => *len,
+        }
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub(crate) fn get(&self, key: &K) -> Option<&V> {
+        match self {
+            IdMap::Hashed(map) => map.get(key),
+            IdMap::Dense { slots, .. } => {
+                let (_, value) = key.dense_index().and_then(|ix| slots.get(ix))?.as_ref()?;
+                Some(value)
+            }
+        }
+    }
+
+    pub(crate) fn get_mut(&mut self, key: &K) -> Option<&mut V> {
+        match self {
+            IdMap::Hashed(map) => map.get_mut(key),
+            IdMap::Dense { slots, .. } => {
+                let (_, value) = key
+                    .dense_index()
+                    .and_then(|ix| slots.get_mut(ix))?
+                    .as_mut()?;
+                Some(value)
+            }
+        }
+    }
+
+    pub(crate) fn contains_key(&self, key: &K) -> bool {
+        self.get(key).is_some()
+    }
+
+    pub(crate) fn insert(&mut self, key: K, value: V) -> Option<V> {
+        match self {
+            IdMap::Hashed(map) => map.insert(key, value),
+            IdMap::Dense { slots, len } => {
+                let ix = slot(&key);
+                if ix >= slots.len() {
+                    slots.resize_with(ix + 1, || None);
+                }
+                let old = slots[ix].replace((key, value)).map(|(_, old)| old);
+                if old.is_none() {
+                    *len += 1;
+                }
+                old
+            }
+        }
+    }
+
+    pub(crate) fn remove(&mut self, key: &K) -> Option<V> {
+        match self {
+            IdMap::Hashed(map) => map.remove(key),
+            IdMap::Dense { slots, len } => {
+                let (_, old) = key.dense_index().and_then(|ix| slots.get_mut(ix))?.take()?;
+                *len -= 1;
+                Some(old)
+            }
+        }
+    }
+
+    pub(crate) fn reserve(&mut self, additional: usize) {
+        match self {
+            IdMap::Hashed(map) => map.reserve(additional),
+            IdMap::Dense { slots, .. } => slots.reserve(additional),
+        }
+    }
+
+    pub(crate) fn iter(&self) -> impl Iterator<Item = (&K, &V)> + '_ {
+        match self {
+            IdMap::Hashed(map) => Either::Hashed(map.iter()),
+            IdMap::Dense { slots, .. } => Either::Dense(
+                slots
+                    .iter()
+                    .filter_map(|slot| slot.as_ref().map(|(key, value)| (key, value))),
+            ),
+        }
+    }
+
+    /// Only used by `KVGraph`, which edits data of every node and edge.
+    #[cfg(feature = "uuid")]
+    pub(crate) fn iter_mut(&mut self) -> impl Iterator<Item = (&K, &mut V)> + '_ {
+        match self {
+            IdMap::Hashed(map) => Either::Hashed(map.iter_mut()),
+            IdMap::Dense { slots, .. } => Either::Dense(
+                slots
+                    .iter_mut()
+                    .filter_map(|slot| slot.as_mut().map(|(key, value)| (&*key, value))),
+            ),
+        }
+    }
+
+    pub(crate) fn keys(&self) -> impl Iterator<Item = &K> + '_ {
+        self.iter().map(|(key, _)| key)
+    }
+
+    pub(crate) fn values(&self) -> impl Iterator<Item = &V> + '_ {
+        self.iter().map(|(_, value)| value)
+    }
+}
+
+#[cfg(feature = "rayon")]
+impl<K: HgNode + Sync, V: Sync> IdMap<K, V> {
+    pub(crate) fn par_iter(&self) -> impl rayon::iter::ParallelIterator<Item = (&K, &V)> + '_ {
+        use rayon::prelude::*;
+        match self {
+            IdMap::Hashed(map) => rayon::iter::Either::Left(map.par_iter()),
+            IdMap::Dense { slots, .. } => rayon::iter::Either::Right(
+                slots
+                    .par_iter()
+                    .filter_map(|slot| slot.as_ref().map(|(key, value)| (key, value))),
+            ),
+        }
+    }
+}
+
+/// Only used by `KVGraph`, like [`IdMap::iter_mut`].
+#[cfg(all(feature = "rayon", feature = "uuid"))]
+impl<K: HgNode + Send + Sync, V: Send> IdMap<K, V> {
+    pub(crate) fn par_iter_mut(
+        &mut self,
+    ) -> impl rayon::iter::ParallelIterator<Item = (&K, &mut V)> + '_ {
+        use rayon::prelude::*;
+        match self {
+            IdMap::Hashed(map) => rayon::iter::Either::Left(map.par_iter_mut()),
+            IdMap::Dense { slots, .. } => rayon::iter::Either::Right(
+                slots
+                    .par_iter_mut()
+                    .filter_map(|slot| slot.as_mut().map(|(key, value)| (&*key, value))),
+            ),
+        }
+    }
+}
+
+impl<K: HgNode, V> IntoIterator for IdMap<K, V> {
+    type Item = (K, V);
+    type IntoIter = Either<
+        std::collections::hash_map::IntoIter<K, V>,
+        std::iter::Flatten<std::vec::IntoIter<Option<(K, V)>>>,
+    >;
+
+    fn into_iter(self) -> Self::IntoIter {
+        match self {
+            IdMap::Hashed(map) => Either::Hashed(map.into_iter()),
+            IdMap::Dense { slots, .. } => Either::Dense(slots.into_iter().flatten()),
+        }
+    }
+}
+
+impl<K: HgNode, V> Extend<(K, V)> for IdMap<K, V> {
+    fn extend<T: IntoIterator<Item = (K, V)>>(&mut self, iter: T) {
+        for (key, value) in iter {
+            self.insert(key, value);
+        }
+    }
+}
+
+impl<K: HgNode, V> FromIterator<(K, V)> for IdMap<K, V> {
+    fn from_iter<T: IntoIterator<Item = (K, V)>>(iter: T) -> Self {
+        let mut map = IdMap::default();
+        map.extend(iter);
+        map
+    }
+}
+
+impl<K: HgNode, V: Serialize> Serialize for IdMap<K, V> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut map = serializer.serialize_map(Some(self.len()))?;
+        for (key, value) in self.iter() {
+            map.serialize_entry(key, value)?;
+        }
+        map.end()
+    }
+}
+
+/// Serialized as a plain map, so the storage mode is not recorded and
+/// deserializing always gives hashed storage.
+impl<'de, K, V> Deserialize<'de> for IdMap<K, V>
+where
+    K: HgNode + Deserialize<'de>,
+    V: Deserialize<'de>,
+{
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct MapVisitor<K, V>(PhantomData<(K, V)>);
+
+        impl<'de, K, V> Visitor<'de> for MapVisitor<K, V>
+        where
+            K: HgNode + Deserialize<'de>,
+            V: Deserialize<'de>,
+        {
+            type Value = IdMap<K, V>;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                formatter.write_str("a map of IDs")
+            }
+
+            fn visit_map<A: MapAccess<'de>>(self, mut access: A) -> Result<Self::Value, A::Error> {
+                let mut map = FxHashMap::default();
+                map.reserve(access.size_hint().unwrap_or(0));
+                while let Some((key, value)) = access.next_entry()? {
+                    map.insert(key, value);
+                }
+                Ok(IdMap::Hashed(map))
+            }
+        }
+
+        deserializer.deserialize_map(MapVisitor(PhantomData))
+    }
+}
+
+impl<NodeData, EdgeData, NodeID: HgNode, EdgeID: HgNode>
+    HGraph<NodeData, EdgeData, NodeID, EdgeID>
+{
+    /// Moves the nodes and edges into `Vec`s indexed by ID, so lookups are
+    /// direct indexing and [`nodes`](HGraph::nodes) and
+    /// [`edges`](HGraph::edges) come out in increasing ID order. Meant for
+    /// the sequential IDs handed out by `add_node` and `add_edge`, memory
+    /// grows with the largest ID rather than the number of nodes or edges.
+    /// Panics if the ID types cannot be used as indices, like `Uuid`. The
+    /// storage mode is not serialized.
+    pub fn use_dense_storage(&mut self) {
+        if self.has_dense_storage() {
+            return;
+        }
+        self.nodes = std::mem::take(&mut self.nodes).into_dense();
+        self.edges = std::mem::take(&mut self.edges).into_dense();
+    }
+
+    /// Moves the nodes and edges back into hash maps, the default.
+    pub fn use_hashed_storage(&mut self) {
+        self.nodes = std::mem::take(&mut self.nodes).into_hashed();
+        self.edges = std::mem::take(&mut self.edges).into_hashed();
+    }
+
+    pub fn has_dense_storage(&self) -> bool {
+        self.nodes.is_dense()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{ConGraph, HGraph, HyperGraph};
+
+    #[test]
+    fn dense_matches_hashed() {
+        let mut hashed = HGraph::<u8, char>::new();
+        let mut dense = HGraph::<u8, char>::new();
+        dense.use_dense_storage();
+        for hg in [&mut hashed, &mut dense] {
+            for x in 0..6 {
+                hg.add_node(x);
+            }
+            hg.add_edge([0, 1], 'a');
+            hg.add_edge([0, 1, 2], 'b');
+            hg.add_edge([2, 3, 4], 'c');
+            hg.remove_node(3);
+            hg.remove_edge(0);
+        }
+        assert!(dense.has_dense_storage());
+        assert_eq!(dense.nodes(), vec![0, 1, 2, 4, 5]);
+        assert_eq!(dense.edges(), vec![1, 2]);
+        assert_eq!(dense.get_edge(&2), hashed.get_edge(&2));
+        assert_eq!(dense.query_edge(&2), Some(vec![2, 4]));
+        assert_eq!(dense.get_node(&3), None);
+        assert_eq!(dense.link_of_nodes([2]), hashed.link_of_nodes([2]));
+        assert_eq!(dense.num_nodes(), 5);
+
+        dense.use_hashed_storage();
+        assert!(!dense.has_dense_storage());
+        assert_eq!(dense.num_edges(), 2);
+        assert_eq!(dense.add_node(6), 6);
+    }
+
+    #[test]
+    fn congraph_is_dense() {
+        let mut cg = ConGraph::new();
+        let nodes = cg.add_nodes(4);
+        cg.add_edge([nodes[3], nodes[1]]);
+        cg.add_edge([nodes[0], nodes[1]]);
+        assert!(cg.core.has_dense_storage());
+        assert_eq!(cg.nodes(), nodes);
+        assert_eq!(cg.edges(), vec![0, 1]);
+
+        let json = serde_json::to_string(&cg).unwrap();
+        let back: ConGraph = serde_json::from_str(&json).unwrap();
+        assert!(back.core.has_dense_storage());
+        assert_eq!(back, cg);
+    }
+}
@@ -0,0 +1,175 @@
+use std::collections::VecDeque;
+
+use fxhash::FxHashMap;
+use serde::{Deserialize, Serialize};
+
+use crate::{HGraph, HgNode};
+
+/// How a hypergraph hands out IDs for new nodes or edges.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum IdPolicy {
+    /// IDs are a counter and are never reused, the default.
+    #[default]
+    Monotonic,
+    /// IDs of removed nodes or edges are reused, oldest removal first.
+    ReuseFifo,
+    /// IDs of removed nodes or edges are reused, latest removal first.
+    ReuseLifo,
+}
+
+/// The reuse side of ID allocation, the counter for fresh IDs stays on the
+/// graph. Only keeps freed IDs while a reuse policy is active.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(crate) struct IdAllocator<ID: HgNode> {
+    policy: IdPolicy,
+    free: VecDeque<ID>,
+    /// How many times each ID has been handed out again, which tells handles
+    /// to a reused ID apart.
+    #[serde(skip, default = "FxHashMap::default")]
+    reuses: FxHashMap<ID, u32>,
+}
+
+impl<ID: HgNode> Default for IdAllocator<ID> {
+    fn default() -> Self {
+        IdAllocator {
+            policy: IdPolicy::Monotonic,
+            free: VecDeque::new(),
+            reuses: FxHashMap::default(),
+        }
+    }
+}
+
+impl<ID: HgNode> IdAllocator<ID> {
+    /// A previously freed ID to use next, `None` if a fresh ID is needed.
+    pub(crate) fn take_free(&mut self) -> Option<ID> {
+        let id = match self.policy {
+            IdPolicy::Monotonic => None,
+            IdPolicy::ReuseFifo => self.free.pop_front(),
+            IdPolicy::ReuseLifo => self.free.pop_back(),
+        }?;
+        *self.reuses.entry(id).or_insert(0) += 1;
+        Some(id)
+    }
+
+    /// Whether [`take_free`](IdAllocator::take_free) would hand out an ID.
+    pub(crate) fn can_reuse(&self) -> bool {
+        self.policy != IdPolicy::Monotonic && !self.free.is_empty()
+    }
+
+    pub(crate) fn release(&mut self, id: ID) {
+        if self.policy != IdPolicy::Monotonic {
+            self.free.push_back(id);
+        }
+    }
+
+    pub(crate) fn reuses(&self, id: &ID) -> u32 {
+        self.reuses.get(id).cloned().unwrap_or(0)
+    }
+
+    pub(crate) fn policy(&self) -> IdPolicy {
+        self.policy
+    }
+
+    /// Switching to [`IdPolicy::Monotonic`] forgets the freed IDs.
+    pub(crate) fn set_policy(&mut self, policy: IdPolicy) {
+        if policy == IdPolicy::Monotonic {
+            self.free.clear();
+        }
+        self.policy = policy;
+    }
+
+    /// Forgets every freed ID, for when the IDs have been renumbered.
+    pub(crate) fn clear(&mut self) {
+        self.free.clear();
+        self.reuses.clear();
+    }
+
+    pub(crate) fn retain_free(&mut self, keep: impl Fn(&ID) -> bool) {
+        self.free.retain(|id| keep(id));
+    }
+}
+
+impl<NodeData, EdgeData, NodeID: HgNode, EdgeID: HgNode>
+    HGraph<NodeData, EdgeData, NodeID, EdgeID>
+{
+    /// Sets how IDs of new nodes are chosen. With a reuse policy the IDs of
+    /// nodes removed from then on are handed out again before fresh ones, so
+    /// long running workloads with small ID types only run out of IDs when
+    /// that many nodes are live at once. Switching back to
+    /// [`IdPolicy::Monotonic`] forgets the freed IDs. Handles to a removed
+    /// node stay invalid after its ID is reused.
+    pub fn set_node_id_policy(&mut self, policy: IdPolicy) {
+        self.node_alloc.set_policy(policy);
+    }
+
+    /// Same as [`set_node_id_policy`](HGraph::set_node_id_policy) for edges.
+    pub fn set_edge_id_policy(&mut self, policy: IdPolicy) {
+        self.edge_alloc.set_policy(policy);
+    }
+
+    pub fn node_id_policy(&self) -> IdPolicy {
+        self.node_alloc.policy()
+    }
+
+    pub fn edge_id_policy(&self) -> IdPolicy {
+        self.edge_alloc.policy()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{ConGraph, HGraph, HyperGraph, IdPolicy};
+
+    #[test]
+    fn reuse_order() {
+        let mut hg = HGraph::<(), ()>::new();
+        hg.set_node_id_policy(IdPolicy::ReuseFifo);
+        hg.set_edge_id_policy(IdPolicy::ReuseLifo);
+        hg.add_nodes(4);
+        let e0 = hg.add_edge([0, 1], ());
+        let e1 = hg.add_edge([1, 2], ());
+        hg.add_edge([2, 3], ());
+        hg.remove_edge(e0);
+        hg.remove_edge(e1);
+        hg.remove_node(3);
+        hg.remove_node(0);
+        assert_eq!(hg.add_node(()), 3);
+        assert_eq!(hg.add_node(()), 0);
+        assert_eq!(hg.add_node(()), 4);
+        assert_eq!(hg.add_edge([0, 1], ()), e1);
+        assert_eq!(hg.add_edge([0, 2], ()), e0);
+        assert_eq!(hg.add_edge([0, 3], ()), 3);
+        assert_eq!(hg.incident_edges(&3), vec![3]);
+
+        hg.set_node_id_policy(IdPolicy::Monotonic);
+        hg.remove_node(4);
+        assert_eq!(hg.add_node(()), 5);
+    }
+
+    #[test]
+    fn small_ids_do_not_run_out() {
+        let mut hg = HGraph::<(), (), u8, u8>::new();
+        hg.set_node_id_policy(IdPolicy::ReuseLifo);
+        hg.set_edge_id_policy(IdPolicy::ReuseFifo);
+        let anchor = hg.add_node(());
+        let mut previous = None;
+        for _ in 0..1000 {
+            let node = hg.add_node(());
+            assert_eq!(node, 1);
+            if let Some(handle) = previous {
+                assert!(hg.resolve_node(&handle).is_err());
+            }
+            let edge = hg.add_edge([anchor, node], ());
+            previous = hg.node_handle(node);
+            hg.remove_edge(edge);
+            hg.remove_node(node);
+        }
+        assert_eq!(hg.num_nodes(), 1);
+
+        let mut cg = ConGraph::new();
+        cg.set_node_id_policy(IdPolicy::ReuseFifo);
+        cg.add_nodes(3);
+        cg.remove_node(1);
+        assert_eq!(cg.add_node(), 1);
+    }
+}
@@ -0,0 +1,515 @@
+use std::collections::BTreeMap;
+use std::sync::mpsc::Receiver;
+
+use fxhash::FxHashSet;
+use serde::{Deserialize, Serialize};
+
+use crate::hgraph::{Edge, Node};
+use crate::{EdgeSet, GraphEvent, HGraph, HgNode};
+
+/// One node or edge going from its `old` state to its `new` state, `None`
+/// meaning not present. Undone by swapping the two.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+enum Change<NodeData, EdgeData, NodeID, EdgeID> {
+    Node {
+        id: NodeID,
+        old: Option<NodeData>,
+        new: Option<NodeData>,
+    },
+    Edge {
+        id: EdgeID,
+        old: Option<(Vec<NodeID>, EdgeData)>,
+        new: Option<(Vec<NodeID>, EdgeData)>,
+    },
+}
+
+/// The recorded operations of a [`JournaledHGraph`]. Each operation is
+/// stored as the node and edge changes it made, including the edges a node
+/// removal or concatenation cascaded away, so it can be undone and redone
+/// exactly. Serializable along with the graph it belongs to.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Journal<NodeData, EdgeData, NodeID, EdgeID> {
+    entries: Vec<Vec<Change<NodeData, EdgeData, NodeID, EdgeID>>>,
+    /// How many entries are currently applied, the ones after it can be
+    /// redone.
+    position: usize,
+    checkpoints: BTreeMap<String, usize>,
+}
+
+impl<NodeData, EdgeData, NodeID, EdgeID> Default for Journal<NodeData, EdgeData, NodeID, EdgeID> {
+    fn default() -> Self {
+        Journal {
+            entries: Vec::new(),
+            position: 0,
+            checkpoints: BTreeMap::new(),
+        }
+    }
+}
+
+impl<NodeData, EdgeData, NodeID, EdgeID> Journal<NodeData, EdgeData, NodeID, EdgeID> {
+    /// The number of recorded operations, including undone ones.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// The number of operations currently applied.
+    pub fn position(&self) -> usize {
+        self.position
+    }
+
+    /// The names of the checkpoints that can still be restored.
+    pub fn checkpoints(&self) -> Vec<&str> {
+        self.checkpoints.keys().map(|name| name.as_str()).collect()
+    }
+
+    /// Records an operation, dropping every undone operation and the
+    /// checkpoints among them.
+    fn push(&mut self, changes: Vec<Change<NodeData, EdgeData, NodeID, EdgeID>>) {
+        if changes.is_empty() {
+            return;
+        }
+        self.entries.truncate(self.position);
+        let position = self.position;
+        self.checkpoints.retain(|_, at| *at <= position);
+        self.entries.push(changes);
+        self.position += 1;
+    }
+}
+
+/// An [`HGraph`] that journals its mutations so they can be undone and
+/// redone, for interactive editors and the like. Data is cloned into the
+/// journal, so `NodeData` and `EdgeData` have to be `Clone`. Mutations go
+/// through the wrapper, reads go through [`graph`](JournaledHGraph::graph).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JournaledHGraph<NodeData, EdgeData, NodeID: HgNode = u32, EdgeID: HgNode = u64> {
+    core: HGraph<NodeData, EdgeData, NodeID, EdgeID>,
+    journal: Journal<NodeData, EdgeData, NodeID, EdgeID>,
+}
+
+impl<NodeData, EdgeData, NodeID, EdgeID> From<HGraph<NodeData, EdgeData, NodeID, EdgeID>>
+    for JournaledHGraph<NodeData, EdgeData, NodeID, EdgeID>
+where
+    NodeID: HgNode,
+    EdgeID: HgNode,
+{
+    /// Starts journaling an existing graph, its current state is the
+    /// furthest anything can be undone to.
+    fn from(core: HGraph<NodeData, EdgeData, NodeID, EdgeID>) -> Self {
+        JournaledHGraph {
+            core,
+            journal: Journal::default(),
+        }
+    }
+}
+
+impl<NodeData, EdgeData, NodeID, EdgeID> Default
+    for JournaledHGraph<NodeData, EdgeData, NodeID, EdgeID>
+where
+    NodeData: Clone,
+    EdgeData: Clone,
+    NodeID: HgNode,
+    EdgeID: HgNode,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<NodeData, EdgeData, NodeID, EdgeID> JournaledHGraph<NodeData, EdgeData, NodeID, EdgeID>
+where
+    NodeData: Clone,
+    EdgeData: Clone,
+    NodeID: HgNode,
+    EdgeID: HgNode,
+{
+    pub fn new() -> Self {
+        HGraph::new().into()
+    }
+
+    pub fn graph(&self) -> &HGraph<NodeData, EdgeData, NodeID, EdgeID> {
+        &self.core
+    }
+
+    pub fn journal(&self) -> &Journal<NodeData, EdgeData, NodeID, EdgeID> {
+        &self.journal
+    }
+
+    /// Forgets the journal, keeping the graph as is.
+    pub fn clear_journal(&mut self) {
+        self.journal = Journal::default();
+    }
+
+    pub fn into_inner(self) -> HGraph<NodeData, EdgeData, NodeID, EdgeID> {
+        self.core
+    }
+
+    /// See [`HGraph::subscribe`].
+    pub fn subscribe(
+        &mut self,
+        callback: impl FnMut(&GraphEvent<NodeID, EdgeID>) + Send + Sync + 'static,
+    ) -> usize {
+        self.core.subscribe(callback)
+    }
+
+    /// See [`HGraph::unsubscribe`].
+    pub fn unsubscribe(&mut self, subscriber: usize) -> bool {
+        self.core.unsubscribe(subscriber)
+    }
+
+    /// See [`HGraph::event_channel`].
+    pub fn event_channel(&mut self) -> Receiver<GraphEvent<NodeID, EdgeID>> {
+        self.core.event_channel()
+    }
+
+    /// See [`HGraph::add_node`].
+    pub fn add_node(&mut self, data: NodeData) -> NodeID {
+        let id = self.core.add_node(data.clone());
+        self.journal.push(vec![Change::Node {
+            id,
+            old: None,
+            new: Some(data),
+        }]);
+        id
+    }
+
+    /// See [`HGraph::add_edge`].
+    pub fn add_edge(&mut self, nodes: impl AsRef<[NodeID]>, data: EdgeData) -> EdgeID {
+        let old = self
+            .core
+            .find_id(nodes.as_ref())
+            .and_then(|id| self.edge_state(&id));
+        let id = self.core.add_edge(nodes, data);
+        let new = self.edge_state(&id);
+        self.journal.push(vec![Change::Edge { id, old, new }]);
+        id
+    }
+
+    /// See [`HGraph::remove_edge`].
+    pub fn remove_edge(&mut self, edge: EdgeID) -> Option<EdgeData> {
+        let old = self.edge_state(&edge)?;
+        let data = self.core.remove_edge(edge);
+        self.journal.push(vec![Change::Edge {
+            id: edge,
+            old: Some(old),
+            new: None,
+        }]);
+        data
+    }
+
+    /// See [`HGraph::remove_node`], undoing it also brings back the edges
+    /// it shrank or removed.
+    pub fn remove_node(&mut self, node: NodeID) -> Option<NodeData> {
+        self.record(&[node], |core| core.remove_node(node))
+    }
+
+    /// See [`HGraph::concatenate_nodes`].
+    pub fn concatenate_nodes(&mut self, node1: &NodeID, node2: &NodeID) {
+        self.record(&[*node1, *node2], |core| {
+            core.concatenate_nodes(node1, node2)
+        })
+    }
+
+    /// Replaces the data of the node, returning the old data.
+    pub fn set_node_data(&mut self, node: &NodeID, data: NodeData) -> Option<NodeData> {
+        let old = self.core.insert_node_data(node, data.clone())?;
+        self.journal.push(vec![Change::Node {
+            id: *node,
+            old: Some(old.clone()),
+            new: Some(data),
+        }]);
+        Some(old)
+    }
+
+    /// Replaces the data of the edge, returning the old data.
+    pub fn set_edge_data(&mut self, edge: &EdgeID, data: EdgeData) -> Option<EdgeData> {
+        let old = self.edge_state(edge)?;
+        self.core.insert_edge_data(edge, data);
+        let new = self.edge_state(edge);
+        let old_data = old.1.clone();
+        self.journal.push(vec![Change::Edge {
+            id: *edge,
+            old: Some(old),
+            new,
+        }]);
+        Some(old_data)
+    }
+
+    pub fn can_undo(&self) -> bool {
+        self.journal.position > 0
+    }
+
+    pub fn can_redo(&self) -> bool {
+        self.journal.position < self.journal.entries.len()
+    }
+
+    /// Undoes the latest applied operation, returns `false` if there is
+    /// nothing to undo.
+    pub fn undo(&mut self) -> bool {
+        if !self.can_undo() {
+            return false;
+        }
+        self.journal.position -= 1;
+        for change in self.journal.entries[self.journal.position].iter().rev() {
+            match change {
+                Change::Node { id, old, .. } => self.core.set_node_state(*id, old.clone()),
+                Change::Edge { id, old, .. } => self.core.set_edge_state(*id, old.clone()),
+            }
+        }
+        true
+    }
+
+    /// Redoes the latest undone operation, returns `false` if there is
+    /// nothing to redo. Any new operation drops the undone ones.
+    pub fn redo(&mut self) -> bool {
+        if !self.can_redo() {
+            return false;
+        }
+        for change in self.journal.entries[self.journal.position].iter() {
+            match change {
+                Change::Node { id, new, .. } => self.core.set_node_state(*id, new.clone()),
+                Change::Edge { id, new, .. } => self.core.set_edge_state(*id, new.clone()),
+            }
+        }
+        self.journal.position += 1;
+        true
+    }
+
+    /// Names the current state so it can be returned to with
+    /// [`restore_checkpoint`](JournaledHGraph::restore_checkpoint), replacing
+    /// a checkpoint with the same name.
+    pub fn checkpoint(&mut self, name: impl Into<String>) {
+        self.journal
+            .checkpoints
+            .insert(name.into(), self.journal.position);
+    }
+
+    /// Undoes or redoes operations until the graph is back at the
+    /// checkpoint. Fails if there is no such checkpoint, checkpoints made
+    /// after an undone operation are dropped once a new operation is made.
+    pub fn restore_checkpoint(&mut self, name: &str) -> Result<(), String> {
+        let Some(target) = self.journal.checkpoints.get(name).cloned() else {
+            return Err(format!("No checkpoint named {:?}.", name));
+        };
+        while self.journal.position > target {
+            self.undo();
+        }
+        while self.journal.position < target {
+            self.redo();
+        }
+        Ok(())
+    }
+
+    fn edge_state(&self, edge: &EdgeID) -> Option<(Vec<NodeID>, EdgeData)> {
+        self.core
+            .edges
+            .get(edge)
+            .map(|edge| (edge.nodes.node_vec(), edge.data.clone()))
+    }
+
+    /// Runs an operation that can only change `nodes` and the edges
+    /// containing them, and journals whatever it changed. Edges come first
+    /// so removed nodes are no longer in any edge when replayed.
+    fn record<T>(
+        &mut self,
+        nodes: &[NodeID],
+        op: impl FnOnce(&mut HGraph<NodeData, EdgeData, NodeID, EdgeID>) -> T,
+    ) -> T {
+        let edges: FxHashSet<EdgeID> = nodes
+            .iter()
+            .filter_map(|node| self.core.nodes.get(node))
+            .flat_map(|node| node.containing_edges.iter().cloned())
+            .collect();
+        let old_edges: Vec<_> = edges
+            .into_iter()
+            .map(|id| (id, self.edge_state(&id)))
+            .collect();
+        let old_nodes: Vec<_> = nodes
+            .iter()
+            .map(|id| (*id, self.core.get_node(id).cloned()))
+            .collect();
+        let out = op(&mut self.core);
+
+        let mut changes = Vec::new();
+        for (id, old) in old_edges {
+            let new = self.edge_state(&id);
+            if old.as_ref().map(|edge| &edge.0) != new.as_ref().map(|edge| &edge.0) {
+                changes.push(Change::Edge { id, old, new });
+            }
+        }
+        for (id, old) in old_nodes {
+            let new = self.core.get_node(&id).cloned();
+            if old.is_some() != new.is_some() {
+                changes.push(Change::Node { id, old, new });
+            }
+        }
+        self.journal.push(changes);
+        out
+    }
+}
+
+impl<NodeData, EdgeData, NodeID: HgNode, EdgeID: HgNode>
+    HGraph<NodeData, EdgeData, NodeID, EdgeID>
+{
+    /// Puts the node in the given state. A node is only ever removed here
+    /// once its edges are gone.
+    fn set_node_state(&mut self, id: NodeID, data: Option<NodeData>) {
+        match data {
+            Some(data) if self.nodes.contains_key(&id) => {
+                self.insert_node_data(&id, data);
+            }
+            Some(data) => {
+                self.node_alloc.retain_free(|free| *free != id);
+                self.nodes.insert(id, Node::new(data));
+                self.emit(GraphEvent::NodeAdded(id));
+            }
+            None => {
+                if self.nodes.remove(&id).is_some() {
+                    self.node_alloc.release(id);
+                    self.emit(GraphEvent::NodeRemoved(id));
+                }
+            }
+        }
+    }
+
+    fn set_edge_state(&mut self, id: EdgeID, state: Option<(Vec<NodeID>, EdgeData)>) {
+        self.unindex_edge(id);
+        let old = self.edges.remove(&id);
+        if let Some(old) = old.as_ref() {
+            for node in old.nodes.0.iter() {
+                if let Some(node) = self.nodes.get_mut(node) {
+                    node.containing_edges.remove(&id);
+                }
+            }
+            if state.is_none() {
+                self.edge_alloc.release(id);
+            }
+        } else if state.is_some() {
+            self.edge_alloc.retain_free(|free| *free != id);
+        }
+        let event = match (&old, &state) {
+            (None, None) => return,
+            (None, Some(_)) => GraphEvent::EdgeAdded(id),
+            (Some(_), None) => GraphEvent::EdgeRemoved(id),
+            (Some(old), Some((nodes, _))) if old.nodes.0.as_slice() == nodes.as_slice() => {
+                GraphEvent::EdgeDataChanged(id)
+            }
+            (Some(_), Some(_)) => GraphEvent::EdgeNodesChanged(id),
+        };
+        let Some((nodes, data)) = state else {
+            self.emit(event);
+            return;
+        };
+        for node in nodes.iter() {
+            self.nodes
+                .get_mut(node)
+                .expect("Journaled edge node is not present.")
+                .containing_edges
+                .insert(id);
+        }
+        let nodes = EdgeSet::from(nodes);
+        self.edges.insert(id, Edge { nodes, data });
+        self.index_edge(id);
+        self.emit(event);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{HGraph, HyperGraph, IdPolicy, JournaledHGraph};
+
+    fn snapshot(hg: &HGraph<char, u8>) -> String {
+        let mut nodes: Vec<_> = hg
+            .nodes()
+            .into_iter()
+            .map(|id| (id, hg.get_node(&id)))
+            .collect();
+        nodes.sort();
+        let mut edges: Vec<_> = hg
+            .edges()
+            .into_iter()
+            .map(|id| (id, hg.query_edge(&id), hg.get_edge(&id)))
+            .collect();
+        edges.sort();
+        format!("{:?} {:?}", nodes, edges)
+    }
+
+    #[test]
+    fn undo_and_redo_cascades() {
+        let mut hg = JournaledHGraph::<char, u8>::new();
+        let a = hg.add_node('a');
+        let b = hg.add_node('b');
+        let c = hg.add_node('c');
+        hg.add_edge([a], 0);
+        hg.add_edge([a, b], 1);
+        hg.add_edge([a, c], 2);
+        hg.add_edge([b, c], 3);
+        hg.set_node_data(&c, 'C');
+        hg.checkpoint("built");
+        let built = snapshot(hg.graph());
+
+        assert_eq!(hg.remove_node(a), Some('a'));
+        hg.concatenate_nodes(&b, &c);
+        let edited = snapshot(hg.graph());
+        assert_eq!(hg.graph().num_nodes(), 1);
+
+        assert!(hg.undo());
+        assert!(hg.undo());
+        assert_eq!(snapshot(hg.graph()), built);
+        assert_eq!(hg.graph().find_id([a, c]), Some(2));
+        assert!(hg.redo());
+        assert!(hg.redo());
+        assert!(!hg.redo());
+        assert_eq!(snapshot(hg.graph()), edited);
+
+        hg.restore_checkpoint("built").unwrap();
+        assert_eq!(snapshot(hg.graph()), built);
+        while hg.undo() {}
+        assert_eq!(hg.graph().num_nodes(), 0);
+        assert!(hg.restore_checkpoint("missing").is_err());
+        hg.restore_checkpoint("built").unwrap();
+        assert_eq!(snapshot(hg.graph()), built);
+    }
+
+    #[test]
+    fn new_operations_drop_undone_ones() {
+        let mut hg = JournaledHGraph::<char, u8>::new();
+        let a = hg.add_node('a');
+        hg.checkpoint("one node");
+        let b = hg.add_node('b');
+        hg.checkpoint("two nodes");
+        let e = hg.add_edge([a, b], 0);
+        hg.undo();
+        hg.undo();
+        hg.set_node_data(&a, 'A');
+        assert!(!hg.can_redo());
+        assert_eq!(hg.journal().len(), 2);
+        assert_eq!(hg.journal().checkpoints(), vec!["one node"]);
+        assert!(hg.graph().get_edge(&e).is_none());
+
+        let json = serde_json::to_string(&hg).unwrap();
+        let mut loaded: JournaledHGraph<char, u8> = serde_json::from_str(&json).unwrap();
+        loaded.restore_checkpoint("one node").unwrap();
+        assert_eq!(loaded.graph().get_node(&a), Some(&'a'));
+        loaded.undo();
+        assert_eq!(loaded.graph().num_nodes(), 0);
+    }
+
+    #[test]
+    fn undone_ids_are_not_reused() {
+        let mut core = HGraph::<char, u8>::new();
+        core.set_node_id_policy(IdPolicy::ReuseLifo);
+        let mut hg = JournaledHGraph::from(core);
+        let a = hg.add_node('a');
+        let b = hg.add_node('b');
+        hg.remove_node(b);
+        hg.undo();
+        let c = hg.add_node('c');
+        assert_ne!(c, b);
+        hg.add_edge([a, b, c], 0);
+        assert_eq!(hg.graph().degree(&b), Some(1));
+    }
+}
@@ -0,0 +1,481 @@
+use std::borrow::Borrow;
+use std::hash::Hash;
+use std::sync::mpsc::Receiver;
+
+use fxhash::FxHashMap;
+
+use crate::{GraphEvent, HGraph, HgNode, HyperGraph};
+
+/// An [`HGraph`] whose nodes are identified by user provided keys, such as
+/// gene names or user handles, instead of only by their `NodeID`s. Keys and
+/// IDs are mapped in both directions and kept in sync through every
+/// mutation. Everything that takes nodes accepts keys, anything that can be
+/// borrowed as the key type works so `&str`s can be used with `String`
+/// keys. The [`HyperGraph`] trait is implemented on the underlying IDs, with
+/// `_of_keys` versions of the node based queries that return nothing if any
+/// key has no node.
+#[derive(Debug, Clone)]
+pub struct KeyedHGraph<K: Hash + Eq, NodeData, EdgeData, NodeID: HgNode = u32, EdgeID: HgNode = u64>
+{
+    core: HGraph<NodeData, EdgeData, NodeID, EdgeID>,
+    ids: FxHashMap<K, NodeID>,
+    keys: FxHashMap<NodeID, K>,
+}
+
+impl<K: Hash + Eq, NodeData, EdgeData, NodeID: HgNode, EdgeID: HgNode> Default
+    for KeyedHGraph<K, NodeData, EdgeData, NodeID, EdgeID>
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K: Hash + Eq, NodeData, EdgeData, NodeID: HgNode, EdgeID: HgNode>
+    KeyedHGraph<K, NodeData, EdgeData, NodeID, EdgeID>
+{
+    pub fn new() -> Self {
+        KeyedHGraph {
+            core: HGraph::new(),
+            ids: FxHashMap::default(),
+            keys: FxHashMap::default(),
+        }
+    }
+
+    /// Adds a node for `key`, or replaces the data of the node already
+    /// using it. Returns the ID of the node.
+    pub fn add_node(&mut self, key: K, data: NodeData) -> NodeID
+    where
+        K: Clone,
+    {
+        if let Some(id) = self.ids.get(&key) {
+            self.core.insert_node_data(id, data);
+            return *id;
+        }
+        let id = self.core.add_node(data);
+        self.ids.insert(key.clone(), id);
+        self.keys.insert(id, key);
+        id
+    }
+
+    /// Removes the node along with its key, see [`HGraph::remove_node`].
+    pub fn remove_node<Q>(&mut self, key: &Q) -> Option<NodeData>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        let id = self.ids.remove(key)?;
+        self.keys.remove(&id);
+        self.core.remove_node(id)
+    }
+
+    /// Creates an edge among the nodes of the provided keys, replacing the
+    /// data if the edge already exists. Returns an error if a key has no
+    /// node.
+    pub fn add_edge<'a, Q>(
+        &mut self,
+        keys: impl IntoIterator<Item = &'a Q>,
+        data: EdgeData,
+    ) -> Result<EdgeID, String>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized + 'a,
+    {
+        let mut nodes = Vec::new();
+        for (ix, key) in keys.into_iter().enumerate() {
+            match self.ids.get(key) {
+                Some(id) => nodes.push(*id),
+                None => return Err(format!("Key number {} has no node.", ix)),
+            }
+        }
+        Ok(self.core.add_edge(nodes, data))
+    }
+
+    pub fn remove_edge(&mut self, edge: EdgeID) -> Option<EdgeData> {
+        self.core.remove_edge(edge)
+    }
+
+    /// Returns the ID of the edge consisting of exactly the nodes of the
+    /// provided keys, if there is one.
+    pub fn find_id<'a, Q>(&self, keys: impl IntoIterator<Item = &'a Q>) -> Option<EdgeID>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized + 'a,
+    {
+        self.core.find_id(self.ids_of(keys)?)
+    }
+
+    /// The node ID behind `key`.
+    pub fn id_of<Q>(&self, key: &Q) -> Option<NodeID>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.ids.get(key).cloned()
+    }
+
+    /// The key of a node ID.
+    pub fn key_of(&self, node: &NodeID) -> Option<&K> {
+        self.keys.get(node)
+    }
+
+    /// The node IDs of all the keys, `None` if any key has no node.
+    pub fn ids_of<'a, Q>(&self, keys: impl IntoIterator<Item = &'a Q>) -> Option<Vec<NodeID>>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized + 'a,
+    {
+        keys.into_iter().map(|key| self.id_of(key)).collect()
+    }
+
+    /// The keys of the node IDs, skipping IDs that are not present.
+    pub fn keys_of(&self, nodes: impl AsRef<[NodeID]>) -> Vec<&K> {
+        nodes
+            .as_ref()
+            .iter()
+            .filter_map(|node| self.keys.get(node))
+            .collect()
+    }
+
+    /// The keys of the nodes in the edge.
+    pub fn edge_keys(&self, edge: &EdgeID) -> Option<Vec<&K>> {
+        self.core.query_edge(edge).map(|nodes| self.keys_of(nodes))
+    }
+
+    pub fn get_node<Q>(&self, key: &Q) -> Option<&NodeData>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.core.get_node(self.ids.get(key)?)
+    }
+
+    pub fn get_node_mut<Q>(&mut self, key: &Q) -> Option<&mut NodeData>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.core.get_node_mut(self.ids.get(key)?)
+    }
+
+    pub fn get_edge(&self, edge: &EdgeID) -> Option<&EdgeData> {
+        self.core.get_edge(edge)
+    }
+
+    pub fn get_edge_mut(&mut self, edge: &EdgeID) -> Option<&mut EdgeData> {
+        self.core.get_edge_mut(edge)
+    }
+
+    /// Borrows the underlying hypergraph, for running anything written
+    /// against `HGraph`. Mutable access is not given out as it could
+    /// remove nodes without updating the keys.
+    pub fn as_hgraph(&self) -> &HGraph<NodeData, EdgeData, NodeID, EdgeID> {
+        &self.core
+    }
+
+    /// Consumes the keyed graph, returning the underlying hypergraph and the
+    /// map from keys to IDs.
+    pub fn into_parts(
+        self,
+    ) -> (
+        HGraph<NodeData, EdgeData, NodeID, EdgeID>,
+        FxHashMap<K, NodeID>,
+    ) {
+        (self.core, self.ids)
+    }
+
+    /// See [`HGraph::subscribe`].
+    pub fn subscribe(
+        &mut self,
+        callback: impl FnMut(&GraphEvent<NodeID, EdgeID>) + Send + Sync + 'static,
+    ) -> usize {
+        self.core.subscribe(callback)
+    }
+
+    /// See [`HGraph::unsubscribe`].
+    pub fn unsubscribe(&mut self, subscriber: usize) -> bool {
+        self.core.unsubscribe(subscriber)
+    }
+
+    /// See [`HGraph::event_channel`].
+    pub fn event_channel(&mut self) -> Receiver<GraphEvent<NodeID, EdgeID>> {
+        self.core.event_channel()
+    }
+
+    pub fn incident_edges_of_key<Q>(&self, key: &Q) -> Vec<EdgeID>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        match self.ids.get(key) {
+            Some(id) => self.core.incident_edges(id),
+            None => Vec::new(),
+        }
+    }
+
+    /// See [`HyperGraph::containing_edges_of_nodes`].
+    pub fn containing_edges_of_keys<'a, Q>(
+        &self,
+        keys: impl IntoIterator<Item = &'a Q>,
+    ) -> Vec<EdgeID>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized + 'a,
+    {
+        self.ids_of(keys)
+            .map(|nodes| self.core.containing_edges_of_nodes(nodes))
+            .unwrap_or_default()
+    }
+
+    /// See [`HyperGraph::link_of_nodes`], with the links given as keys.
+    pub fn link_of_keys<'a, Q>(
+        &self,
+        keys: impl IntoIterator<Item = &'a Q>,
+    ) -> Vec<(EdgeID, Vec<&K>)>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized + 'a,
+    {
+        self.ids_of(keys)
+            .map(|nodes| {
+                self.core
+                    .link_of_nodes(nodes)
+                    .into_iter()
+                    .map(|(id, link)| (id, self.keys_of(link)))
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// See [`HyperGraph::maximal_edges_of_nodes`].
+    pub fn maximal_edges_of_keys<'a, Q>(&self, keys: impl IntoIterator<Item = &'a Q>) -> Vec<EdgeID>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized + 'a,
+    {
+        self.ids_of(keys)
+            .map(|nodes| self.core.maximal_edges_of_nodes(nodes))
+            .unwrap_or_default()
+    }
+
+    /// See [`HyperGraph::boundary_up_of_nodes`], with the edges given as keys.
+    pub fn boundary_up_of_keys<'a, Q>(&self, keys: impl IntoIterator<Item = &'a Q>) -> Vec<Vec<&K>>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized + 'a,
+    {
+        self.ids_of(keys)
+            .map(|nodes| self.as_keys(self.core.boundary_up_of_nodes(nodes)))
+            .unwrap_or_default()
+    }
+
+    /// See [`HyperGraph::boundary_down_of_nodes`], with the edges given as
+    /// keys.
+    pub fn boundary_down_of_keys<'a, Q>(
+        &self,
+        keys: impl IntoIterator<Item = &'a Q>,
+    ) -> Vec<Vec<&K>>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized + 'a,
+    {
+        self.ids_of(keys)
+            .map(|nodes| self.as_keys(self.core.boundary_down_of_nodes(nodes)))
+            .unwrap_or_default()
+    }
+
+    /// See [`HyperGraph::faces_of_nodes`].
+    pub fn faces_of_keys<'a, Q>(&self, keys: impl IntoIterator<Item = &'a Q>) -> Vec<EdgeID>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized + 'a,
+    {
+        self.ids_of(keys)
+            .map(|nodes| self.core.faces_of_nodes(nodes))
+            .unwrap_or_default()
+    }
+
+    fn as_keys(&self, edges: Vec<Vec<NodeID>>) -> Vec<Vec<&K>> {
+        edges.into_iter().map(|nodes| self.keys_of(nodes)).collect()
+    }
+}
+
+impl<K: Hash + Eq, NodeData, EdgeData, NodeID: HgNode, EdgeID: HgNode> HyperGraph
+    for KeyedHGraph<K, NodeData, EdgeData, NodeID, EdgeID>
+{
+    type NodeID = NodeID;
+    type EdgeID = EdgeID;
+
+    fn query_edge(&self, edge: &Self::EdgeID) -> Option<Vec<Self::NodeID>> {
+        self.core.query_edge(edge)
+    }
+
+    fn node_ids(&self) -> impl Iterator<Item = Self::NodeID> + '_ {
+        self.core.node_ids()
+    }
+
+    fn edge_ids(&self) -> impl Iterator<Item = Self::EdgeID> + '_ {
+        self.core.edge_ids()
+    }
+
+    fn num_nodes(&self) -> usize {
+        self.core.num_nodes()
+    }
+
+    fn num_edges(&self) -> usize {
+        self.core.num_edges()
+    }
+
+    fn degree(&self, node: &Self::NodeID) -> Option<usize> {
+        self.core.degree(node)
+    }
+
+    fn edge_size(&self, edge: &Self::EdgeID) -> Option<usize> {
+        self.core.edge_size(edge)
+    }
+
+    fn incident_edges(&self, node: &Self::NodeID) -> Vec<Self::EdgeID> {
+        self.core.incident_edges(node)
+    }
+
+    fn containing_edges_of_nodes(&self, nodes: impl AsRef<[Self::NodeID]>) -> Vec<Self::EdgeID> {
+        self.core.containing_edges_of_nodes(nodes)
+    }
+
+    fn containing_edges(&self, edge: &Self::EdgeID) -> Vec<Self::EdgeID> {
+        self.core.containing_edges(edge)
+    }
+
+    fn link(&self, edge: &Self::EdgeID) -> Vec<(Self::EdgeID, Vec<Self::NodeID>)> {
+        self.core.link(edge)
+    }
+
+    fn link_of_nodes(
+        &self,
+        nodes: impl AsRef<[Self::NodeID]>,
+    ) -> Vec<(Self::EdgeID, Vec<Self::NodeID>)> {
+        self.core.link_of_nodes(nodes)
+    }
+
+    fn maximal_edges(&self, edge_id: &Self::EdgeID) -> Vec<Self::EdgeID> {
+        self.core.maximal_edges(edge_id)
+    }
+
+    fn maximal_edges_of_nodes(&self, nodes: impl AsRef<[Self::NodeID]>) -> Vec<Self::EdgeID> {
+        self.core.maximal_edges_of_nodes(nodes)
+    }
+
+    fn edges_of_size(&self, card: usize) -> Vec<Self::EdgeID> {
+        self.core.edges_of_size(card)
+    }
+
+    fn skeleton(&self, cardinality: usize) -> Vec<Self::EdgeID> {
+        self.core.skeleton(cardinality)
+    }
+
+    fn boundary_up(&self, edge_id: &Self::EdgeID) -> Vec<Vec<Self::NodeID>> {
+        self.core.boundary_up(edge_id)
+    }
+
+    fn boundary_down(&self, edge_id: &Self::EdgeID) -> Vec<Vec<Self::NodeID>> {
+        self.core.boundary_down(edge_id)
+    }
+
+    fn boundary_up_of_nodes(&self, nodes: impl AsRef<[Self::NodeID]>) -> Vec<Vec<Self::NodeID>> {
+        self.core.boundary_up_of_nodes(nodes)
+    }
+
+    fn boundary_down_of_nodes(&self, nodes: impl AsRef<[Self::NodeID]>) -> Vec<Vec<Self::NodeID>> {
+        self.core.boundary_down_of_nodes(nodes)
+    }
+
+    fn containing_edges_iter(
+        &self,
+        edge: &Self::EdgeID,
+    ) -> impl Iterator<Item = Self::EdgeID> + '_ {
+        self.core.containing_edges_iter(edge)
+    }
+
+    fn maximal_edges_iter(
+        &self,
+        edge_id: &Self::EdgeID,
+    ) -> impl Iterator<Item = Self::EdgeID> + '_ {
+        self.core.maximal_edges_iter(edge_id)
+    }
+
+    fn faces(&self, edge_id: &Self::EdgeID) -> Vec<Self::EdgeID> {
+        self.core.faces(edge_id)
+    }
+
+    fn faces_of_nodes(&self, nodes: impl AsRef<[Self::NodeID]>) -> Vec<Self::EdgeID> {
+        self.core.faces_of_nodes(nodes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{sorted, HyperGraph, KeyedHGraph};
+
+    fn genes() -> KeyedHGraph<String, f32, &'static str> {
+        let mut hg = KeyedHGraph::new();
+        for (ix, gene) in ["brca1", "tp53", "egfr", "myc"].iter().enumerate() {
+            hg.add_node(gene.to_string(), ix as f32);
+        }
+        hg.add_edge(["brca1", "tp53"], "repair").unwrap();
+        hg.add_edge(["brca1", "tp53", "egfr"], "pathway").unwrap();
+        hg.add_edge(["egfr", "myc"], "growth").unwrap();
+        hg
+    }
+
+    #[test]
+    fn keys_follow_mutations() {
+        let mut hg = genes();
+        assert_eq!(hg.get_node("egfr"), Some(&2.0));
+        assert_eq!(hg.add_node("egfr".to_string(), 7.0), 2);
+        assert_eq!(hg.get_node("egfr"), Some(&7.0));
+        let pathway = hg.find_id(["egfr", "tp53", "brca1"]).unwrap();
+        assert_eq!(hg.get_edge(&pathway), Some(&"pathway"));
+        assert!(hg.add_edge(["brca1", "kras"], "missing").is_err());
+        assert_eq!(hg.edge_keys(&pathway).unwrap().len(), 3);
+
+        assert_eq!(hg.remove_node("tp53"), Some(1.0));
+        assert_eq!(hg.id_of("tp53"), None);
+        assert_eq!(hg.key_of(&1), None);
+        assert!(hg.find_id(["brca1", "tp53"]).is_none());
+        assert_eq!(hg.find_id(["brca1", "egfr"]), Some(pathway));
+        let tp53 = hg.add_node("tp53".to_string(), 3.0);
+        assert_eq!(tp53, 4);
+        assert_eq!(hg.key_of(&tp53).map(|key| key.as_str()), Some("tp53"));
+        assert_eq!(hg.num_nodes(), 4);
+    }
+
+    #[test]
+    fn keyed_queries() {
+        let hg = genes();
+        let repair = hg.find_id(["brca1", "tp53"]).unwrap();
+        let pathway = hg.find_id(["brca1", "tp53", "egfr"]).unwrap();
+        let growth = hg.find_id(["egfr", "myc"]).unwrap();
+        assert_eq!(
+            sorted(hg.containing_edges_of_keys(["brca1"])),
+            vec![repair, pathway]
+        );
+        assert!(hg.containing_edges_of_keys(["kras"]).is_empty());
+        assert_eq!(hg.maximal_edges_of_keys(["brca1"]), vec![pathway]);
+        let link = hg.link_of_keys(["brca1", "tp53"]);
+        assert_eq!(link.len(), 1);
+        assert_eq!(link[0].0, pathway);
+        assert_eq!(link[0].1, vec!["egfr"]);
+        assert_eq!(hg.boundary_up_of_keys(["tp53", "brca1"]).len(), 1);
+        assert_eq!(
+            sorted(hg.faces_of_keys(["brca1", "tp53", "egfr", "myc"])),
+            vec![repair, pathway, growth]
+        );
+        assert!(hg.faces_of_keys(["brca1", "tp53", "kras"]).is_empty());
+        assert_eq!(
+            sorted(hg.incident_edges_of_key("egfr")),
+            vec![pathway, growth]
+        );
+        let tp53 = hg.id_of("tp53").unwrap();
+        assert_eq!(hg.degree(&tp53), Some(2));
+        assert_eq!(hg.as_hgraph().num_edges(), 3);
+    }
+}
@@ -1,5 +1,7 @@
 #[cfg(feature = "polars")]
 use polars::prelude::*;
+#[cfg(feature = "rayon")]
+use rayon::prelude::*;
 
 use std::{collections::HashMap, path::PathBuf, str::FromStr};
 
@@ -7,7 +9,7 @@ use indexmap::IndexMap;
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
-use crate::{EdgeSet, HGraph, HyperGraph};
+use crate::{EdgeSet, HGraph, HyperGraph, HyperGraphMut};
 
 /// The data types of a possible [`Value`](`crate::kvgraph::Value`) that can be stored in a [`KVGraph`](`crate::KVGraph`)
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
@@ -369,9 +371,8 @@ impl KVGraph {
         self.core.edges.keys().cloned().collect()
     }
 
-    /// Removes a node from the node set. The deleted node will be added to a
-    /// dequeue to be reused later once all possible nodes have been created.
-    /// The data stored will be dropped.
+    /// Removes a node along with its data, edges left without any nodes are
+    /// removed as well. Node IDs are random `Uuid`s and are not reused.
     pub fn remove_node(&mut self, node: Uuid) -> Option<HashMap<String, Value>> {
         self.core.remove_node(node)
     }
@@ -507,6 +508,29 @@ impl KVGraph {
         ret
     }
 
+    /// Parallel version of [`remove_all_keys`](KVGraph::remove_all_keys).
+    #[cfg(feature = "rayon")]
+    pub fn par_remove_all_keys(&mut self, key: &str) -> Vec<(Uuid, Value)> {
+        if !self.schema.contains_key(key) {
+            return Vec::new();
+        }
+        let key_string = key.to_string();
+        let mut ret: Vec<(Uuid, Value)> = self
+            .core
+            .nodes
+            .par_iter_mut()
+            .filter_map(|(id, node)| node.data.remove(&key_string).map(|value| (*id, value)))
+            .collect();
+        ret.par_extend(
+            self.core
+                .edges
+                .par_iter_mut()
+                .filter_map(|(id, edge)| edge.data.remove(&key_string).map(|value| (*id, value))),
+        );
+        self.schema.swap_remove(&key_string);
+        ret
+    }
+
     fn nodes_string(&self, id: &Uuid) -> Option<String> {
         let mut s = String::from("[");
         if self.core.nodes.contains_key(&id) {
@@ -577,131 +601,156 @@ impl KVGraph {
     pub fn dataframe_of_ids(&self, ids: impl AsRef<[Uuid]>) -> DataFrame {
         let mut df = DataFrame::default();
         ids.as_ref()
-            .into_iter()
-            .map(|id| (id, id.to_string()))
-            .filter(|(id, _)| self.core.nodes.contains_key(id) || self.core.edges.contains_key(id))
-            .for_each(|(id, id_string)| {
-                let mut id_df = DataFrame::default();
-                let kv_store = if self.core.nodes.contains_key(&id) {
-                    &self.core.nodes.get(&id).unwrap().data
-                } else {
-                    &self.core.edges.get(&id).unwrap().data
-                };
-
-                for (key, dtype) in self.schema.iter() {
-                    if &key[..] == "id" {
-                        id_df
-                            .with_column(Series::new("id", [id_string.clone()]))
-                            .expect("couldn't add column.");
-                    } else if &key[..] == "nodes" {
-                        let node_string = self
-                            .nodes_string(&id)
-                            .expect("ID was checked in previous filter.");
-                        id_df
-                            .with_column(Series::new("nodes", [node_string]))
-                            .expect("What error");
-                    } else if &key[..] == "labelled_nodes" {
-                        let labelled_nodes = self
-                            .labelled_nodes_string(&id)
-                            .expect("ID was checked in previous filter.");
-                        id_df
-                            .with_column(Series::new("labelled_nodes", [labelled_nodes]))
-                            .expect("What error");
-                    } else {
-                        match dtype {
-                            ValueTypes::Bool => {
-                                let s = Series::new(
-                                    &key[..],
-                                    [kv_store.get(key).map(|val| Into::<bool>::into(val.clone()))],
-                                );
-                                id_df.with_column(s).expect("Couldn't add column.");
-                            }
-                            ValueTypes::UInt8 => {
-                                let s = Series::new(
-                                    &key[..],
-                                    [kv_store.get(key).map(|val| Into::<u8>::into(val.clone()))],
-                                );
-                                id_df.with_column(s).expect("Couldn't add column.");
-                            }
-                            ValueTypes::UInt16 => {
-                                let s = Series::new(
-                                    &key[..],
-                                    [kv_store.get(key).map(|val| Into::<u16>::into(val.clone()))],
-                                );
-                                id_df.with_column(s).expect("Couldn't add column.");
-                            }
-                            ValueTypes::UInt32 => {
-                                let s = Series::new(
-                                    &key[..],
-                                    [kv_store.get(key).map(|val| Into::<u32>::into(val.clone()))],
-                                );
-                                id_df.with_column(s).expect("Couldn't add column.");
-                            }
-                            ValueTypes::UInt64 => {
-                                let s = Series::new(
-                                    &key[..],
-                                    [kv_store.get(key).map(|val| Into::<u64>::into(val.clone()))],
-                                );
-                                id_df.with_column(s).expect("Couldn't add column.");
-                            }
-                            ValueTypes::Int8 => {
-                                let s = Series::new(
-                                    &key[..],
-                                    [kv_store.get(key).map(|val| Into::<i8>::into(val.clone()))],
-                                );
-                                id_df.with_column(s).expect("Couldn't add column.");
-                            }
-                            ValueTypes::Int16 => {
-                                let s = Series::new(
-                                    &key[..],
-                                    [kv_store.get(key).map(|val| Into::<i16>::into(val.clone()))],
-                                );
-                                id_df.with_column(s).expect("Couldn't add column.");
-                            }
-                            ValueTypes::Int32 => {
-                                let s = Series::new(
-                                    &key[..],
-                                    [kv_store.get(key).map(|val| Into::<i32>::into(val.clone()))],
-                                );
-                                id_df.with_column(s).expect("Couldn't add column.");
-                            }
-                            ValueTypes::Int64 => {
-                                let s = Series::new(
-                                    &key[..],
-                                    [kv_store.get(key).map(|val| Into::<i64>::into(val.clone()))],
-                                );
-                                id_df.with_column(s).expect("Couldn't add column.");
-                            }
-                            ValueTypes::Float32 => {
-                                let s = Series::new(
-                                    &key[..],
-                                    [kv_store.get(key).map(|val| Into::<f32>::into(val.clone()))],
-                                );
-                                id_df.with_column(s).expect("Couldn't add column.");
-                            }
-                            ValueTypes::Float64 => {
-                                let s = Series::new(
-                                    &key[..],
-                                    [kv_store.get(key).map(|val| Into::<f64>::into(val.clone()))],
-                                );
-                                id_df.with_column(s).expect("Couldn't add column.");
-                            }
-                            ValueTypes::String => {
-                                let s = Series::new(
-                                    &key[..],
-                                    [kv_store
-                                        .get(key)
-                                        .map(|val| Into::<String>::into(val.clone()))],
-                                );
-                                id_df.with_column(s).expect("Couldn't add column.");
-                            }
-                        };
-                    }
-                }
-                df.vstack_mut(&id_df).expect("Could not vstack");
+            .iter()
+            .filter(|id| self.core.nodes.contains_key(id) || self.core.edges.contains_key(id))
+            .for_each(|id| {
+                df.vstack_mut(&self.id_dataframe(id))
+                    .expect("Could not vstack");
             });
         df
     }
+
+    /// Parallel version of [`dataframe_of_ids`](KVGraph::dataframe_of_ids).
+    /// The single row frames are built in parallel and then stacked in the
+    /// order of `ids`.
+    #[cfg(all(feature = "polars", feature = "rayon"))]
+    pub fn par_dataframe_of_ids(&self, ids: impl AsRef<[Uuid]>) -> DataFrame {
+        let frames: Vec<DataFrame> = ids
+            .as_ref()
+            .par_iter()
+            .filter(|id| self.core.nodes.contains_key(id) || self.core.edges.contains_key(id))
+            .map(|id| self.id_dataframe(id))
+            .collect();
+        let mut df = DataFrame::default();
+        for id_df in frames.iter() {
+            df.vstack_mut(id_df).expect("Could not vstack");
+        }
+        df
+    }
+
+    /// The single row dataframe of a node or edge that is known to exist.
+    #[cfg(feature = "polars")]
+    fn id_dataframe(&self, id: &Uuid) -> DataFrame {
+        let id_string = id.to_string();
+        let mut id_df = DataFrame::default();
+        let kv_store = if self.core.nodes.contains_key(&id) {
+            &self.core.nodes.get(&id).unwrap().data
+        } else {
+            &self.core.edges.get(&id).unwrap().data
+        };
+
+        for (key, dtype) in self.schema.iter() {
+            if &key[..] == "id" {
+                id_df
+                    .with_column(Series::new("id", [id_string.clone()]))
+                    .expect("couldn't add column.");
+            } else if &key[..] == "nodes" {
+                let node_string = self
+                    .nodes_string(&id)
+                    .expect("ID was checked in previous filter.");
+                id_df
+                    .with_column(Series::new("nodes", [node_string]))
+                    .expect("What error");
+            } else if &key[..] == "labelled_nodes" {
+                let labelled_nodes = self
+                    .labelled_nodes_string(&id)
+                    .expect("ID was checked in previous filter.");
+                id_df
+                    .with_column(Series::new("labelled_nodes", [labelled_nodes]))
+                    .expect("What error");
+            } else {
+                match dtype {
+                    ValueTypes::Bool => {
+                        let s = Series::new(
+                            &key[..],
+                            [kv_store.get(key).map(|val| Into::<bool>::into(val.clone()))],
+                        );
+                        id_df.with_column(s).expect("Couldn't add column.");
+                    }
+                    ValueTypes::UInt8 => {
+                        let s = Series::new(
+                            &key[..],
+                            [kv_store.get(key).map(|val| Into::<u8>::into(val.clone()))],
+                        );
+                        id_df.with_column(s).expect("Couldn't add column.");
+                    }
+                    ValueTypes::UInt16 => {
+                        let s = Series::new(
+                            &key[..],
+                            [kv_store.get(key).map(|val| Into::<u16>::into(val.clone()))],
+                        );
+                        id_df.with_column(s).expect("Couldn't add column.");
+                    }
+                    ValueTypes::UInt32 => {
+                        let s = Series::new(
+                            &key[..],
+                            [kv_store.get(key).map(|val| Into::<u32>::into(val.clone()))],
+                        );
+                        id_df.with_column(s).expect("Couldn't add column.");
+                    }
+                    ValueTypes::UInt64 => {
+                        let s = Series::new(
+                            &key[..],
+                            [kv_store.get(key).map(|val| Into::<u64>::into(val.clone()))],
+                        );
+                        id_df.with_column(s).expect("Couldn't add column.");
+                    }
+                    ValueTypes::Int8 => {
+                        let s = Series::new(
+                            &key[..],
+                            [kv_store.get(key).map(|val| Into::<i8>::into(val.clone()))],
+                        );
+                        id_df.with_column(s).expect("Couldn't add column.");
+                    }
+                    ValueTypes::Int16 => {
+                        let s = Series::new(
+                            &key[..],
+                            [kv_store.get(key).map(|val| Into::<i16>::into(val.clone()))],
+                        );
+                        id_df.with_column(s).expect("Couldn't add column.");
+                    }
+                    ValueTypes::Int32 => {
+                        let s = Series::new(
+                            &key[..],
+                            [kv_store.get(key).map(|val| Into::<i32>::into(val.clone()))],
+                        );
+                        id_df.with_column(s).expect("Couldn't add column.");
+                    }
+                    ValueTypes::Int64 => {
+                        let s = Series::new(
+                            &key[..],
+                            [kv_store.get(key).map(|val| Into::<i64>::into(val.clone()))],
+                        );
+                        id_df.with_column(s).expect("Couldn't add column.");
+                    }
+                    ValueTypes::Float32 => {
+                        let s = Series::new(
+                            &key[..],
+                            [kv_store.get(key).map(|val| Into::<f32>::into(val.clone()))],
+                        );
+                        id_df.with_column(s).expect("Couldn't add column.");
+                    }
+                    ValueTypes::Float64 => {
+                        let s = Series::new(
+                            &key[..],
+                            [kv_store.get(key).map(|val| Into::<f64>::into(val.clone()))],
+                        );
+                        id_df.with_column(s).expect("Couldn't add column.");
+                    }
+                    ValueTypes::String => {
+                        let s = Series::new(
+                            &key[..],
+                            [kv_store
+                                .get(key)
+                                .map(|val| Into::<String>::into(val.clone()))],
+                        );
+                        id_df.with_column(s).expect("Couldn't add column.");
+                    }
+                };
+            }
+        }
+        id_df
+    }
     /// Collects the dataframe for all nodes in the hypergraph. If a key is not
     /// present for a node then 'null' is used in the dataframe.
     #[cfg(feature = "polars")]
@@ -768,6 +817,34 @@ impl HyperGraph for KVGraph {
         self.core.query_edge(edge)
     }
 
+    fn node_ids(&self) -> impl Iterator<Item = Self::NodeID> + '_ {
+        self.core.node_ids()
+    }
+
+    fn edge_ids(&self) -> impl Iterator<Item = Self::EdgeID> + '_ {
+        self.core.edge_ids()
+    }
+
+    fn num_nodes(&self) -> usize {
+        self.core.num_nodes()
+    }
+
+    fn num_edges(&self) -> usize {
+        self.core.num_edges()
+    }
+
+    fn degree(&self, node: &Self::NodeID) -> Option<usize> {
+        self.core.degree(node)
+    }
+
+    fn edge_size(&self, edge: &Self::EdgeID) -> Option<usize> {
+        self.core.edge_size(edge)
+    }
+
+    fn incident_edges(&self, node: &Self::NodeID) -> Vec<Self::EdgeID> {
+        self.core.incident_edges(node)
+    }
+
     fn containing_edges_of_nodes(&self, nodes: impl AsRef<[Self::NodeID]>) -> Vec<Self::EdgeID> {
         self.core.containing_edges_of_nodes(nodes.as_ref())
     }
@@ -818,6 +895,145 @@ impl HyperGraph for KVGraph {
     fn boundary_down_of_nodes(&self, nodes: impl AsRef<[Self::NodeID]>) -> Vec<Vec<Self::NodeID>> {
         self.core.boundary_down_of_nodes(nodes)
     }
+
+    fn containing_edges_iter(
+        &self,
+        edge: &Self::EdgeID,
+    ) -> impl Iterator<Item = Self::EdgeID> + '_ {
+        self.core.containing_edges_iter(edge)
+    }
+
+    fn containing_edges_of_nodes_iter(
+        &self,
+        nodes: impl AsRef<[Self::NodeID]>,
+    ) -> impl Iterator<Item = Self::EdgeID> + '_ {
+        self.core.containing_edges_of_nodes_iter(nodes)
+    }
+
+    fn link_iter(
+        &self,
+        edge: &Self::EdgeID,
+    ) -> impl Iterator<Item = (Self::EdgeID, Vec<Self::NodeID>)> + '_ {
+        self.core.link_iter(edge)
+    }
+
+    fn link_of_nodes_iter(
+        &self,
+        nodes: impl AsRef<[Self::NodeID]>,
+    ) -> impl Iterator<Item = (Self::EdgeID, Vec<Self::NodeID>)> + '_ {
+        self.core.link_of_nodes_iter(nodes)
+    }
+
+    fn maximal_edges_iter(
+        &self,
+        edge_id: &Self::EdgeID,
+    ) -> impl Iterator<Item = Self::EdgeID> + '_ {
+        self.core.maximal_edges_iter(edge_id)
+    }
+
+    fn maximal_edges_of_nodes_iter(
+        &self,
+        nodes: impl AsRef<[Self::NodeID]>,
+    ) -> impl Iterator<Item = Self::EdgeID> + '_ {
+        self.core.maximal_edges_of_nodes_iter(nodes)
+    }
+
+    fn edges_of_size_iter(&self, card: usize) -> impl Iterator<Item = Self::EdgeID> + '_ {
+        self.core.edges_of_size_iter(card)
+    }
+
+    fn skeleton_iter(&self, cardinality: usize) -> impl Iterator<Item = Self::EdgeID> + '_ {
+        self.core.skeleton_iter(cardinality)
+    }
+
+    fn boundary_up_ids_iter(
+        &self,
+        edge_id: &Self::EdgeID,
+    ) -> impl Iterator<Item = Self::EdgeID> + '_ {
+        self.core.boundary_up_ids_iter(edge_id)
+    }
+
+    fn boundary_down_ids_iter(
+        &self,
+        edge_id: &Self::EdgeID,
+    ) -> impl Iterator<Item = Self::EdgeID> + '_ {
+        self.core.boundary_down_ids_iter(edge_id)
+    }
+
+    fn boundary_up_of_nodes_ids_iter(
+        &self,
+        nodes: impl AsRef<[Self::NodeID]>,
+    ) -> impl Iterator<Item = Self::EdgeID> + '_ {
+        self.core.boundary_up_of_nodes_ids_iter(nodes)
+    }
+
+    fn boundary_down_of_nodes_ids_iter(
+        &self,
+        nodes: impl AsRef<[Self::NodeID]>,
+    ) -> impl Iterator<Item = Self::EdgeID> + '_ {
+        self.core.boundary_down_of_nodes_ids_iter(nodes)
+    }
+
+    fn faces(&self, edge_id: &Self::EdgeID) -> Vec<Self::EdgeID> {
+        self.core.faces(edge_id)
+    }
+
+    fn faces_of_nodes(&self, nodes: impl AsRef<[Self::NodeID]>) -> Vec<Self::EdgeID> {
+        self.core.faces_of_nodes(nodes)
+    }
+
+    fn cofaces(&self, edge_id: &Self::EdgeID) -> Vec<Self::EdgeID> {
+        self.core.cofaces(edge_id)
+    }
+
+    fn open_star(&self, edge_id: &Self::EdgeID) -> Vec<Self::EdgeID> {
+        self.core.open_star(edge_id)
+    }
+
+    fn closed_star(&self, edge_id: &Self::EdgeID) -> Vec<Self::EdgeID> {
+        self.core.closed_star(edge_id)
+    }
+
+    fn deletion(&self, edge_id: &Self::EdgeID) -> Vec<Self::EdgeID> {
+        self.core.deletion(edge_id)
+    }
+}
+
+impl HyperGraphMut for KVGraph {
+    type NodeData = HashMap<String, Value>;
+    type EdgeData = HashMap<String, Value>;
+
+    /// Adds a node with an empty label.
+    fn add_node_default(&mut self) -> Self::NodeID {
+        self.add_node()
+    }
+
+    /// Adds an edge with an empty label if it does not exist yet.
+    fn add_edge_default(&mut self, nodes: impl AsRef<[Self::NodeID]>) -> Self::EdgeID {
+        match self.core.find_id(nodes.as_ref()) {
+            Some(id) => id,
+            None => self.add_edge(nodes),
+        }
+    }
+
+    fn remove_node(&mut self, node: Self::NodeID) -> Option<Self::NodeData> {
+        self.core.remove_node(node)
+    }
+
+    fn remove_edge(&mut self, edge: Self::EdgeID) -> Option<Self::EdgeData> {
+        self.core.remove_edge(edge)
+    }
+
+    fn contract(&mut self, nodes: impl AsRef<[Self::NodeID]>) -> Option<Self::NodeID> {
+        let (target, rest) = nodes.as_ref().split_first()?;
+        if nodes.as_ref().iter().any(|node| !self.has_node(*node)) {
+            return None;
+        }
+        for node in rest {
+            self.core.concatenate_nodes(node, target);
+        }
+        Some(*target)
+    }
 }
 
 #[cfg(test)]
@@ -852,4 +1068,29 @@ mod tests {
         println!("{:}", hg.dataframe_of_edges());
         println!("{:}", hg.dataframe());
     }
+
+    #[test]
+    #[cfg(feature = "rayon")]
+    fn par_remove_all_keys() {
+        let mut hg = KVGraph::new();
+        let n1 = hg.add_node();
+        let n2 = hg.add_node();
+        let e1 = hg.add_edge([n1, n2]);
+        hg.insert(&n1, "weight", 1.0_f32).unwrap();
+        hg.insert(&e1, "weight", 2.0_f32).unwrap();
+        hg.insert(&n2, "defense", 0_u8).unwrap();
+        let mut removed: Vec<_> = hg
+            .par_remove_all_keys("weight")
+            .into_iter()
+            .map(|(id, value)| (id, f32::from(value)))
+            .collect();
+        removed.sort_by_key(|(id, _)| *id);
+        let mut expected = vec![(n1, 1.0), (e1, 2.0)];
+        expected.sort_by_key(|(id, _)| *id);
+        assert_eq!(removed, expected);
+        assert!(hg.get(&n1, "weight").is_none());
+        assert!(!hg.get_schema().contains_key("weight"));
+        assert!(hg.par_remove_all_keys("weight").is_empty());
+        hg.insert(&n1, "weight", "heavy".to_string()).unwrap();
+    }
 }
@@ -61,14 +61,33 @@
 //!
 //! # Hypergraph Structs
 //! - [`ConGraph`](`crate::ConGraph`) - a connectivity only option that uses `u32`'s as IDs for
-//! nodes and `u64`'s for edge IDs with each being a simple counter starting at 0. No data that can be stored within the
+//! nodes and `u64`'s for edge IDs by default, other widths can be picked with type parameters such as `ConGraph::<u16, u32>::default()` or the ID features below, with each being a simple counter starting at 0. No data that can be stored within the
 //! `ConGraph` structure itself. Has simpler API if all you need to analyze is
 //! connectivity.
 //! -[`HGraph`](`crate::HGraph`) - A struct generic over four types: the node data, the edge data, the node IDs, and the edge IDs. There are no trait bounds on the node and edge types. Additionally generic over the size of integers `u8` through `u128`
 //! to store NodeIDs and EdgeIDs with `u32` and `u64` as the default for the respective IDs.
 //! Utilizes an adjacency-list storage system.
+//!   - The storage is kept in hash maps by default, `use_dense_storage`
+//!     switches to `Vec`s indexed by ID for direct lookups and ID ordered
+//!     iteration, which is what `ConGraph` uses.
+//!   - IDs can be wrapped in a [`NodeHandle`](`crate::NodeHandle`) or
+//!     [`EdgeHandle`](`crate::EdgeHandle`), which remember the graph instance
+//!     and ID generation they came from. Using them with another or an outdated
+//!     graph is an error instead of silently returning the wrong data.
+//!   - After many removals `compact` renumbers the nodes and edges densely, and
+//!     `relabel_nodes` applies any other relabeling.
+//!   - For long running workloads with small ID types an
+//!     [`IdPolicy`](`crate::IdPolicy`) can reuse the IDs of removed nodes and
+//!     edges in FIFO or LIFO order.
 //! - [`KVGraph`](`crate::KVGraph`) - A key-value hypergraph where each node and edge allows you
 //! to store simple [`kvgraph::Value`](`crate::kvgraph::Value`)s (of type [`kvgraph::ValueTypes`](`crate::kvgraph::ValueTypes`)) modeled after a simple subset of the Polars `AnyValue<'a>`.
+//! - [`EdgeFilteredView`](`crate::EdgeFilteredView`), [`NodeInducedView`](`crate::NodeInducedView`) and [`StarView`](`crate::StarView`) - borrowed subhypergraphs of an `HGraph` that implement [`HyperGraph`](`crate::HyperGraph`) without copying anything, they can be turned into an owned `HGraph` with `to_hgraph`.
+//! - [`KeyedHGraph`](`crate::KeyedHGraph`) - An `HGraph` whose nodes are identified by arbitrary hashable keys, such as names, mapped to `NodeID`s in both directions. Edges are added and found with slices of keys, and the node based queries have `_of_keys` versions.
+//! - [`JournaledHGraph`](`crate::JournaledHGraph`) - An `HGraph` that records its mutations, including the edges removed along with a node, in a serializable [`Journal`](`crate::Journal`) so they can be undone and redone or rolled back to a named checkpoint.
+//! - [`UniformHGraph`](`crate::UniformHGraph`) - A k-uniform hypergraph with the edge size `K` as a const generic, edges are stored as `[NodeID; K]` arrays and edges of any other size are rejected. Implements [`HyperGraph`](`crate::HyperGraph`) along with k-uniform operations like the adjacency tensor contraction `A·x^{k-1}` and the shadow.
+//! - [`FrozenHGraph`](`crate::FrozenHGraph`) - A read-only snapshot of an `HGraph` created with `freeze`, storing the incidences as contiguous arrays over dense indices for cache friendly queries and a much smaller memory footprint. It implements [`HyperGraph`](`crate::HyperGraph`) and can be turned back into an `HGraph` with `thaw`.
+//!
+//! Edges store their nodes as a sorted [`EdgeSet`](`crate::EdgeSet`), which keeps edges of up to four nodes inline without a heap allocation and implements the set operations as linear merges. For graphs with few nodes [`BitEdgeSet`](`crate::BitEdgeSet`) offers the same operations on a bitset.
 //!
 //! `ConGraph` and `KVGraph` are essentially wrappers around `HGraph` with
 //! slightly tweaked function signatures for adding and deleting nodes or edges
@@ -112,6 +131,15 @@
 //! - **"polars"** to compute [`polars`](https://www.pola.rs) dataframes of
 //! any collection of nodes or edges.
 //!
+//! The **"u128_id"** feature switches the default [`ConGraph`](`crate::ConGraph`) IDs, [`DefaultNodeID`](`crate::DefaultNodeID`) and [`DefaultEdgeID`](`crate::DefaultEdgeID`), to `u128` for both nodes and edges, and **"u32_edge_id"** uses `u32` edge IDs, taking precedence over **"u128_id"** for edges.
+//!
+//! The **"rayon"** feature adds parallel versions of the bulk queries, such
+//! as [`par_edges_of_size`](`HGraph::par_edges_of_size`),
+//! [`par_skeleton`](`HGraph::par_skeleton`),
+//! [`par_filter_by_edge`](`HGraph::par_filter_by_edge`) and
+//! [`par_links`](`HGraph::par_links`) for the link of every edge, along with
+//! `KVGraph::par_remove_all_keys` and `KVGraph::par_dataframe_of_ids`.
+//!
 //! # Traits
 //! - [`HyperGraph`](`crate::HyperGraph`) - A collection of functions for querying the adjacency
 //! structure of a hypergraph. There are a few main functions, each of which
@@ -124,10 +152,27 @@
 //!     - [`link`](`HyperGraph::link`) takes all edges which contain the given edge and computes the complement of the input within that edge.
 //!     - [`boundary_up`](`HyperGraph::boundary_up`) the boundary up operator comes from topology and the terminology of simplicial complexes. It takes the input edge and finds all edges that are only a single extra node added to the input.
 //!     - [`boundary_down`](`HyperGraph::boundary_down`) similar to the `boundary_up` operator but removes a node.
+//!     - [`faces`](`HyperGraph::faces`) and [`cofaces`](`HyperGraph::cofaces`) find every edge contained in or containing the input edge at any cardinality, with [`open_star`](`HyperGraph::open_star`), [`closed_star`](`HyperGraph::closed_star`) and [`deletion`](`HyperGraph::deletion`) built on top of them.
+//!     - [`node_ids`](`HyperGraph::node_ids`), [`edge_ids`](`HyperGraph::edge_ids`), [`degree`](`HyperGraph::degree`), [`edge_size`](`HyperGraph::edge_size`) and [`incident_edges`](`HyperGraph::incident_edges`) enumerate and count the hypergraph so algorithms can be written against the trait alone.
+//!     - Each query also has a lazy `_iter` version, such as [`containing_edges_iter`](`HyperGraph::containing_edges_iter`), that borrows from the hypergraph instead of allocating a `Vec`. The boundary versions, such as [`boundary_up_ids_iter`](`HyperGraph::boundary_up_ids_iter`), yield edge IDs instead of node lists.
+//!
+//! - [`HyperGraphMut`](`crate::HyperGraphMut`) - Adding and removing nodes and edges with default data along with node contraction, implemented by all three structures so that mutating algorithms can be written once.
 //!
 //! - [`HgNode`](`crate::HgNode`) - A marker trait for indicating which types are usuable for
 //! node and edge IDs (spoiler: `u8`, `u16, `u32`, `u64`, and `u132`. Don't use `Uuid`s even though they implement the trait.)
 //!
+//! # Algorithms
+//!
+//! - [`HGraph::canonical_labeling`](`crate::HGraph::canonical_labeling`) and [`HGraph::canonical_form`](`crate::HGraph::canonical_form`) compute a canonical relabeling of the nodes (along with automorphism group generators) so isomorphic hypergraphs can be compared directly. [`HGraph::wl_hash`](`crate::HGraph::wl_hash`) is a cheaper Weisfeiler-Lehman hash for bucketing.
+//! - [`motifs`](`crate::motifs`) counts the 26 connected three hyperedge motifs of Lee et al. along with open and simplicially closed node triangles.
+//! - [`HGraph::core_decomposition`](`crate::HGraph::core_decomposition`) and [`HGraph::kt_core_decomposition`](`crate::HGraph::kt_core_decomposition`) compute node coreness for k-cores and (k, t)-cores, [`HGraph::k_core`](`crate::HGraph::k_core`) and [`HGraph::kt_core`](`crate::HGraph::kt_core`) extract the core as a new `HGraph`.
+//! - [`HGraph::estrada_clustering`](`crate::HGraph::estrada_clustering`), [`HGraph::zhou_clustering`](`crate::HGraph::zhou_clustering`) and their local variants compute clustering coefficients without projecting to a graph. [`HGraph::overlap_coefficients`](`crate::HGraph::overlap_coefficients`) and [`HGraph::edge_intersection_profile`](`crate::HGraph::edge_intersection_profile`) summarize how edges overlap.
+//! - [`HGraph::similar_edges`](`crate::HGraph::similar_edges`) and [`HGraph::top_k_similar_edges`](`crate::HGraph::top_k_similar_edges`) find edges with high Jaccard similarity to a query, optionally backed by a MinHash/LSH index enabled with [`HGraph::enable_similarity_index`](`crate::HGraph::enable_similarity_index`).
+//! - [`HGraph::supersets_of_nodes`](`crate::HGraph::supersets_of_nodes`) and [`HGraph::subsets_of_nodes`](`crate::HGraph::subsets_of_nodes`) answer inclusion queries, [`HGraph::enable_inclusion_index`](`crate::HGraph::enable_inclusion_index`) keeps a set-trie that speeds these up along with `containing_edges` and `maximal_edges`.
+//! - [`HGraph::facets`](`crate::HGraph::facets`) lists the maximal edges of the whole hypergraph and [`HGraph::to_simple`](`crate::HGraph::to_simple`) drops every edge contained in another, [`HGraph::enable_facet_tracking`](`crate::HGraph::enable_facet_tracking`) keeps the facets up to date as the hypergraph changes.
+//! - [`HGraph::transaction`](`crate::HGraph::transaction`) applies a batch of node and edge additions, removals and data edits atomically through a [`Transaction`](`crate::Transaction`), rolling all of them back if any fails, and updates the edge indexes once per batch.
+//! - [`HGraph::subscribe`](`crate::HGraph::subscribe`) and [`HGraph::event_channel`](`crate::HGraph::event_channel`) deliver a typed [`GraphEvent`](`crate::GraphEvent`) for every change to the hypergraph, including the edges removed or shrunk along with a node, to a callback or an `mpsc` channel.
+//!
 //! # Alternative Hypergraph Libraries
 //! This library should be considered as an **alpha** version. Here are a few
 //! hypergraph libraries I found, the most mature of which is HyperNetX
@@ -139,24 +184,62 @@
 //! - [HypergraphDB](https://hypergraphdb.org/) (Java): A database backend for storing and querying data, seems unmaintained.
 //! - [Hypergraph](https://crates.io/crates/hypergraph) (Rust): Seemed limited in scope and a bit complicated to me.
 
+mod canonical;
+mod clustering;
+mod compact;
 mod congraph;
+mod cores;
 mod edge;
+mod events;
+mod facets;
+mod frozen;
+mod handle;
 mod hgraph;
 mod hypergraph;
+mod id_map;
+mod id_policy;
+mod journal;
+mod keyed;
 mod node_trait;
+#[cfg(feature = "rayon")]
+mod parallel;
+mod set_trie;
+mod similarity;
+mod transaction;
+mod uniform;
+mod views;
 
-pub use congraph::ConGraph;
-pub use edge::EdgeSet;
+pub use canonical::CanonicalLabeling;
+pub use congraph::{ConGraph, DefaultEdgeID, DefaultNodeID};
+pub use cores::CoreDecomposition;
+pub use edge::{BitEdgeSet, EdgeSet};
+pub use events::GraphEvent;
+pub use frozen::FrozenHGraph;
+pub use handle::{EdgeHandle, NodeHandle};
 pub use hgraph::HGraph;
-pub use hypergraph::HyperGraph;
+pub use hypergraph::{HyperGraph, HyperGraphMut};
+pub use id_policy::IdPolicy;
+pub use journal::{Journal, JournaledHGraph};
+pub use keyed::KeyedHGraph;
+pub use transaction::Transaction;
+pub use uniform::UniformHGraph;
+pub use views::{EdgeFilteredView, NodeInducedView, StarView};
 
 #[cfg(feature = "uuid")]
 pub mod kvgraph;
+pub mod motifs;
 #[cfg(feature = "uuid")]
 pub use kvgraph::KVGraph;
 
 pub use node_trait::HgNode;
 
+/// Sorts query results so tests can compare them.
+#[cfg(test)]
+pub(crate) fn sorted<T: Ord>(mut items: Vec<T>) -> Vec<T> {
+    items.sort();
+    items
+}
+
 #[cfg(test)]
 mod tests {
     #[cfg(feature = "polars")]
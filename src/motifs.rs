@@ -0,0 +1,433 @@
+//! Counting of higher order motifs. An h-motif (Lee, Ko and Shin, 2020)
+//! describes how three connected hyperedges `a`, `b` and `c` overlap by
+//! which of the seven regions of their Venn diagram are empty. Up to
+//! relabeling the three edges there are 26 patterns that can be realized
+//! by distinct and connected hyperedges. Node level configurations of three
+//! nodes, such as open and simplicially closed triangles, are also counted
+//! here.
+
+use std::thread;
+
+use fxhash::FxHashSet;
+
+use crate::{HGraph, HgNode};
+
+/// The number of connected three hyperedge motifs.
+pub const NUM_MOTIFS: usize = 26;
+
+/// The 26 motifs are numbered by increasing canonical region pattern. The
+/// pattern is a 7 bit mask where bit `i` is set if the region of nodes with
+/// membership bitmask `i + 1` is non-empty, using `a = 1`, `b = 2` and
+/// `c = 4`. For example bit 6 is the region `a ∩ b ∩ c`.
+struct MotifTable {
+    /// Maps every realizable region pattern to its motif index.
+    index: [Option<usize>; 128],
+    /// The canonical pattern of each motif.
+    patterns: Vec<u8>,
+}
+
+/// Relabels the edges of a region pattern by the permutation `perm`.
+fn permute_pattern(pattern: u8, perm: [usize; 3]) -> u8 {
+    let mut permuted = 0;
+    for membership in 1..8_usize {
+        if pattern & (1 << (membership - 1)) == 0 {
+            continue;
+        }
+        let mut new_membership = 0;
+        for (edge, image) in perm.iter().enumerate() {
+            if membership & (1 << edge) != 0 {
+                new_membership |= 1 << image;
+            }
+        }
+        permuted |= 1 << (new_membership - 1);
+    }
+    permuted
+}
+
+impl MotifTable {
+    fn new() -> Self {
+        let perms = [
+            [0, 1, 2],
+            [0, 2, 1],
+            [1, 0, 2],
+            [1, 2, 0],
+            [2, 0, 1],
+            [2, 1, 0],
+        ];
+        let mut canonical = [None; 128];
+        for pattern in 0..128_u8 {
+            let regions: Vec<usize> = (1..8).filter(|m| pattern & (1 << (m - 1)) != 0).collect();
+            let pairs = [(0, 1), (0, 2), (1, 2)];
+            let distinct = pairs
+                .iter()
+                .all(|(x, y)| regions.iter().any(|m| (m >> x) & 1 != (m >> y) & 1));
+            let num_overlapping = pairs
+                .iter()
+                .filter(|(x, y)| {
+                    regions
+                        .iter()
+                        .any(|m| (m >> x) & 1 == 1 && (m >> y) & 1 == 1)
+                })
+                .count();
+            if distinct && num_overlapping >= 2 {
+                canonical[pattern as usize] = perms
+                    .iter()
+                    .map(|perm| permute_pattern(pattern, *perm))
+                    .min();
+            }
+        }
+        let mut patterns: Vec<u8> = canonical.iter().flatten().cloned().collect();
+        patterns.sort();
+        patterns.dedup();
+        let mut index = [None; 128];
+        for (pattern, canon) in canonical.iter().enumerate() {
+            if let Some(canon) = canon {
+                index[pattern] = patterns.binary_search(canon).ok();
+            }
+        }
+        MotifTable { index, patterns }
+    }
+}
+
+/// Returns which of the seven Venn regions of the three sorted node slices
+/// are non-empty, in the bit layout described on [`MotifTable`].
+fn region_pattern<N: HgNode>(a: &[N], b: &[N], c: &[N]) -> u8 {
+    let sets = [a, b, c];
+    let mut heads = [0_usize; 3];
+    let mut pattern = 0_u8;
+    loop {
+        let min = (0..3)
+            .filter_map(|ix| sets[ix].get(heads[ix]))
+            .min()
+            .cloned();
+        let Some(min) = min else {
+            return pattern;
+        };
+        let mut membership = 0;
+        for ix in 0..3 {
+            if sets[ix].get(heads[ix]) == Some(&min) {
+                membership |= 1 << ix;
+                heads[ix] += 1;
+            }
+        }
+        pattern |= 1 << (membership - 1);
+    }
+}
+
+/// The canonical Venn region pattern of motif `motif`, see the module docs.
+/// Entry `i` is `true` if the region of nodes belonging to exactly the edges
+/// in the bitmask `i + 1` (`a = 1`, `b = 2`, `c = 4`) is non-empty.
+/// `panic`s if `motif >= NUM_MOTIFS`.
+pub fn motif_regions(motif: usize) -> [bool; 7] {
+    let pattern = MotifTable::new().patterns[motif];
+    let mut regions = [false; 7];
+    for (ix, region) in regions.iter_mut().enumerate() {
+        *region = pattern & (1 << ix) != 0;
+    }
+    regions
+}
+
+/// A motif is closed if all three pairs of its hyperedges overlap and open
+/// if exactly one pair is disjoint.
+pub fn is_closed(motif: usize) -> bool {
+    let regions = motif_regions(motif);
+    [(0, 1), (0, 2), (1, 2)].iter().all(|(x, y)| {
+        (1..8_usize).any(|m| regions[m - 1] && (m >> x) & 1 == 1 && (m >> y) & 1 == 1)
+    })
+}
+
+/// Counts of each h-motif, indexed as described in [`motif_regions`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MotifCounts<EdgeID: HgNode> {
+    pub counts: [usize; NUM_MOTIFS],
+    /// Connected triples in which two edges have the same nodes, which
+    /// `remove_node` can leave behind. They form no motif and are only
+    /// counted here.
+    pub degenerate: usize,
+    /// If requested, `instances[i]` holds every edge triple forming motif `i`
+    /// with the IDs of each triple and the triples themselves sorted.
+    pub instances: Option<Vec<Vec<[EdgeID; 3]>>>,
+}
+
+/// Counts of the configurations formed by triples of nodes in which at least
+/// two of the three pairs of nodes share an edge.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TriangleCounts {
+    /// Exactly two of the three pairs share an edge.
+    pub wedges: usize,
+    /// Every pair shares an edge, but no edge contains all three nodes.
+    pub open_triangles: usize,
+    /// Some edge contains all three nodes, the triangle is simplicially closed.
+    pub closed_triangles: usize,
+}
+
+/// Counts, degenerate triples and instances found by a single worker thread.
+type PartialMotifCounts<EdgeID> = ([usize; NUM_MOTIFS], usize, Vec<Vec<[EdgeID; 3]>>);
+
+fn num_workers(work: usize) -> usize {
+    thread::available_parallelism()
+        .map_or(1, |n| n.get())
+        .min(work)
+        .max(1)
+}
+
+impl<NodeData, EdgeData, NodeID, EdgeID> HGraph<NodeData, EdgeData, NodeID, EdgeID>
+where
+    NodeID: HgNode + Send + Sync,
+    EdgeID: HgNode + Send + Sync,
+{
+    /// Counts the 26 connected three hyperedge motifs, optionally collecting
+    /// every instance. Triples are found by pairing up the neighbors of each
+    /// edge, where two edges are neighbors if they share a node, so only
+    /// connected triples are ever visited. Triples with two equal edges are
+    /// counted as `degenerate` instead. The work is split over the
+    /// available threads.
+    pub fn count_motifs(&self, collect_instances: bool) -> MotifCounts<EdgeID> {
+        let table = MotifTable::new();
+        let mut edge_ids: Vec<EdgeID> = self.edges.keys().cloned().collect();
+        edge_ids.sort();
+        let position = |id: &EdgeID| edge_ids.binary_search(id).unwrap();
+        let edge_nodes: Vec<&[NodeID]> = edge_ids
+            .iter()
+            .map(|id| &self.edges.get(id).unwrap().nodes.0[..])
+            .collect();
+        let neighbors: Vec<Vec<usize>> = (0..edge_ids.len())
+            .map(|ix| {
+                let mut adjacent: Vec<usize> = edge_nodes[ix]
+                    .iter()
+                    .flat_map(|node| self.nodes.get(node).unwrap().containing_edges.iter())
+                    .map(position)
+                    .filter(|jx| *jx != ix)
+                    .collect();
+                adjacent.sort();
+                adjacent.dedup();
+                adjacent
+            })
+            .collect();
+
+        let workers = num_workers(edge_ids.len());
+        let partials: Vec<PartialMotifCounts<EdgeID>> = thread::scope(|s| {
+            let handles: Vec<_> = (0..workers)
+                .map(|worker| {
+                    let (table, edge_ids, edge_nodes, neighbors) =
+                        (&table, &edge_ids, &edge_nodes, &neighbors);
+                    s.spawn(move || {
+                        let mut counts = [0; NUM_MOTIFS];
+                        let mut degenerate = 0;
+                        let mut instances = vec![Vec::new(); NUM_MOTIFS];
+                        for a in (worker..edge_ids.len()).step_by(workers) {
+                            let adjacent = &neighbors[a];
+                            for (ix, b) in adjacent.iter().enumerate() {
+                                for c in adjacent[ix + 1..].iter() {
+                                    // A triple where all three edges overlap is
+                                    // reached from each of them, only count it
+                                    // from the smallest.
+                                    if neighbors[*b].binary_search(c).is_ok() && a > *b {
+                                        continue;
+                                    }
+                                    let pattern = region_pattern(
+                                        edge_nodes[a],
+                                        edge_nodes[*b],
+                                        edge_nodes[*c],
+                                    );
+                                    let Some(motif) = table.index[pattern as usize] else {
+                                        degenerate += 1;
+                                        continue;
+                                    };
+                                    counts[motif] += 1;
+                                    if collect_instances {
+                                        let mut triple = [edge_ids[a], edge_ids[*b], edge_ids[*c]];
+                                        triple.sort();
+                                        instances[motif].push(triple);
+                                    }
+                                }
+                            }
+                        }
+                        (counts, degenerate, instances)
+                    })
+                })
+                .collect();
+            handles
+                .into_iter()
+                .map(|handle| handle.join().expect("Motif worker panicked."))
+                .collect()
+        });
+
+        let mut counts = [0; NUM_MOTIFS];
+        let mut degenerate = 0;
+        let mut instances = vec![Vec::new(); NUM_MOTIFS];
+        for (partial_counts, partial_degenerate, partial_instances) in partials {
+            degenerate += partial_degenerate;
+            for motif in 0..NUM_MOTIFS {
+                counts[motif] += partial_counts[motif];
+            }
+            for (motif, mut found) in partial_instances.into_iter().enumerate() {
+                instances[motif].append(&mut found);
+            }
+        }
+        let instances = if collect_instances {
+            for found in instances.iter_mut() {
+                found.sort();
+            }
+            Some(instances)
+        } else {
+            None
+        };
+        MotifCounts {
+            counts,
+            degenerate,
+            instances,
+        }
+    }
+
+    /// Counts the wedges, open triangles and simplicially closed triangles
+    /// formed by triples of nodes, where two nodes are adjacent if some edge
+    /// contains both of them.
+    pub fn count_node_triangles(&self) -> TriangleCounts {
+        let mut node_ids: Vec<NodeID> = self.nodes.keys().cloned().collect();
+        node_ids.sort();
+        let neighbors: Vec<FxHashSet<NodeID>> = node_ids
+            .iter()
+            .map(|node| {
+                let mut adjacent = FxHashSet::default();
+                for edge_id in self.nodes.get(node).unwrap().containing_edges.iter() {
+                    adjacent.extend(self.edges.get(edge_id).unwrap().nodes.0.iter().cloned());
+                }
+                adjacent.remove(node);
+                adjacent
+            })
+            .collect();
+        let incident_edges: Vec<Vec<&[NodeID]>> = node_ids
+            .iter()
+            .map(|node| {
+                self.nodes
+                    .get(node)
+                    .unwrap()
+                    .containing_edges
+                    .iter()
+                    .map(|edge_id| &self.edges.get(edge_id).unwrap().nodes.0[..])
+                    .collect()
+            })
+            .collect();
+        let position = |node: &NodeID| node_ids.binary_search(node).unwrap();
+
+        let workers = num_workers(node_ids.len());
+        let partials: Vec<TriangleCounts> = thread::scope(|s| {
+            let handles: Vec<_> = (0..workers)
+                .map(|worker| {
+                    let (node_ids, neighbors, incident_edges, position) =
+                        (&node_ids, &neighbors, &incident_edges, &position);
+                    s.spawn(move || {
+                        let mut counts = TriangleCounts::default();
+                        for v in (worker..node_ids.len()).step_by(workers) {
+                            let mut adjacent: Vec<NodeID> = neighbors[v].iter().cloned().collect();
+                            adjacent.sort();
+                            for (ix, u) in adjacent.iter().enumerate() {
+                                for w in adjacent[ix + 1..].iter() {
+                                    if !neighbors[position(u)].contains(w) {
+                                        counts.wedges += 1;
+                                        continue;
+                                    }
+                                    // Triangles are seen from all three
+                                    // nodes, only count from the smallest.
+                                    if node_ids[v] > *u {
+                                        continue;
+                                    }
+                                    let closed = incident_edges[v].iter().any(|edge| {
+                                        edge.binary_search(u).is_ok()
+                                            && edge.binary_search(w).is_ok()
+                                    });
+                                    if closed {
+                                        counts.closed_triangles += 1;
+                                    } else {
+                                        counts.open_triangles += 1;
+                                    }
+                                }
+                            }
+                        }
+                        counts
+                    })
+                })
+                .collect();
+            handles
+                .into_iter()
+                .map(|handle| handle.join().expect("Triangle worker panicked."))
+                .collect()
+        });
+        partials
+            .into_iter()
+            .fold(TriangleCounts::default(), |acc, partial| TriangleCounts {
+                wedges: acc.wedges + partial.wedges,
+                open_triangles: acc.open_triangles + partial.open_triangles,
+                closed_triangles: acc.closed_triangles + partial.closed_triangles,
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::HGraph;
+
+    use super::{is_closed, motif_regions, NUM_MOTIFS};
+
+    #[test]
+    fn motif_table() {
+        let num_closed = (0..NUM_MOTIFS).filter(|motif| is_closed(*motif)).count();
+        // 20 closed and 6 open motifs, as in Lee et al.
+        assert_eq!(num_closed, 20);
+        // Every motif has at least one node in each edge.
+        for motif in 0..NUM_MOTIFS {
+            let regions = motif_regions(motif);
+            for edge in 0..3 {
+                assert!((1..8_usize).any(|m| regions[m - 1] && (m >> edge) & 1 == 1));
+            }
+        }
+    }
+
+    #[test]
+    fn counting() {
+        let mut hg = HGraph::<(), (), u32, u32>::new();
+        hg.add_nodes(7);
+        let e0 = hg.add_edge([0, 1, 2], ());
+        let e1 = hg.add_edge([2, 3, 4], ());
+        let e2 = hg.add_edge([4, 5, 0], ());
+        let e3 = hg.add_edge([6], ());
+        let motifs = hg.count_motifs(true);
+        // Only one connected triple, the "hyper triangle" of e0, e1, e2.
+        assert_eq!(motifs.counts.iter().sum::<usize>(), 1);
+        let instances = motifs.instances.unwrap();
+        let motif = instances.iter().position(|found| found.len() == 1).unwrap();
+        assert_eq!(instances[motif], vec![[e0, e1, e2]]);
+        assert!(is_closed(motif));
+        assert!(instances
+            .iter()
+            .flatten()
+            .all(|triple| !triple.contains(&e3)));
+
+        let triangles = hg.count_node_triangles();
+        // {0, 2, 4} is open while each edge closes its own three nodes.
+        assert_eq!(triangles.open_triangles, 1);
+        assert_eq!(triangles.closed_triangles, 3);
+        assert_eq!(motifs.degenerate, 0);
+    }
+
+    #[test]
+    fn duplicate_edges() {
+        let mut hg = HGraph::<(), ()>::new();
+        hg.add_nodes(5);
+        hg.add_edge([0, 1, 2], ());
+        hg.add_edge([0, 1, 3], ());
+        hg.add_edge([0, 4], ());
+        // Leaves two edges with the nodes {0, 1}.
+        hg.remove_node(2);
+        hg.remove_node(3);
+        let motifs = hg.count_motifs(true);
+        assert_eq!(motifs.counts.iter().sum::<usize>(), 0);
+        assert_eq!(motifs.degenerate, 1);
+        assert!(motifs
+            .instances
+            .unwrap()
+            .iter()
+            .all(|found| found.is_empty()));
+    }
+}
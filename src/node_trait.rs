@@ -13,6 +13,19 @@ pub trait HgNode:
     fn max_number() -> Self;
     fn zero() -> Self;
     fn plus_one(&mut self);
+
+    /// The position of the ID in dense storage, `None` for ID types that
+    /// cannot be used as indices.
+    fn dense_index(&self) -> Option<usize> {
+        None
+    }
+
+    /// The ID at position `ix` in dense storage, the inverse of
+    /// [`dense_index`](HgNode::dense_index).
+    fn from_dense_index(ix: usize) -> Option<Self> {
+        let _ = ix;
+        None
+    }
 }
 
 #[cfg(feature = "uuid")]
@@ -42,6 +55,14 @@ impl HgNode for u128 {
     fn plus_one(&mut self) {
         *self = *self + 1;
     }
+
+    fn dense_index(&self) -> Option<usize> {
+        usize::try_from(*self).ok()
+    }
+
+    fn from_dense_index(ix: usize) -> Option<Self> {
+        u128::try_from(ix).ok()
+    }
 }
 impl HgNode for u64 {
     fn max_number() -> Self {
@@ -55,6 +76,14 @@ impl HgNode for u64 {
     fn plus_one(&mut self) {
         *self = *self + 1;
     }
+
+    fn dense_index(&self) -> Option<usize> {
+        usize::try_from(*self).ok()
+    }
+
+    fn from_dense_index(ix: usize) -> Option<Self> {
+        u64::try_from(ix).ok()
+    }
 }
 impl HgNode for u32 {
     fn max_number() -> Self {
@@ -68,6 +97,14 @@ impl HgNode for u32 {
     fn plus_one(&mut self) {
         *self = *self + 1;
     }
+
+    fn dense_index(&self) -> Option<usize> {
+        usize::try_from(*self).ok()
+    }
+
+    fn from_dense_index(ix: usize) -> Option<Self> {
+        u32::try_from(ix).ok()
+    }
 }
 impl HgNode for u16 {
     fn max_number() -> Self {
@@ -81,6 +118,14 @@ impl HgNode for u16 {
     fn plus_one(&mut self) {
         *self = *self + 1;
     }
+
+    fn dense_index(&self) -> Option<usize> {
+        Some(usize::from(*self))
+    }
+
+    fn from_dense_index(ix: usize) -> Option<Self> {
+        u16::try_from(ix).ok()
+    }
 }
 impl HgNode for u8 {
     fn max_number() -> Self {
@@ -94,4 +139,12 @@ impl HgNode for u8 {
     fn plus_one(&mut self) {
         *self = *self + 1;
     }
+
+    fn dense_index(&self) -> Option<usize> {
+        Some(usize::from(*self))
+    }
+
+    fn from_dense_index(ix: usize) -> Option<Self> {
+        u8::try_from(ix).ok()
+    }
 }
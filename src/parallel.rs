@@ -0,0 +1,107 @@
+use std::collections::HashMap;
+
+use rayon::prelude::*;
+
+use crate::hgraph::Edge;
+use crate::{HGraph, HgNode, HyperGraph};
+
+impl<NodeData, EdgeData, NodeID, EdgeID> HGraph<NodeData, EdgeData, NodeID, EdgeID>
+where
+    NodeID: HgNode + Send + Sync,
+    EdgeID: HgNode + Send + Sync,
+    NodeData: Sync,
+    EdgeData: Sync,
+{
+    /// Parallel version of [`edges_of_size`](HyperGraph::edges_of_size).
+    pub fn par_edges_of_size(&self, card: usize) -> Vec<EdgeID> {
+        self.edges
+            .par_iter()
+            .filter(|(_, edge)| edge.nodes.len() == card)
+            .map(|(id, _)| *id)
+            .collect()
+    }
+
+    /// Parallel version of [`skeleton`](HyperGraph::skeleton).
+    pub fn par_skeleton(&self, cardinality: usize) -> Vec<EdgeID> {
+        self.edges
+            .par_iter()
+            .filter(|(_, edge)| edge.nodes.len() <= cardinality)
+            .map(|(id, _)| *id)
+            .collect()
+    }
+
+    /// Computes the [`link`](HyperGraph::link) of every edge in parallel,
+    /// keyed by edge ID.
+    pub fn par_links(&self) -> HashMap<EdgeID, Vec<(EdgeID, Vec<NodeID>)>> {
+        self.edges
+            .par_iter()
+            .map(|(id, _)| (*id, self.link(id)))
+            .collect()
+    }
+}
+
+impl<NodeData, EdgeData, NodeID, EdgeID> HGraph<NodeData, EdgeData, NodeID, EdgeID>
+where
+    NodeID: HgNode + Send + Sync,
+    EdgeID: HgNode + Send + Sync,
+    NodeData: Clone + Sync,
+    EdgeData: Clone + Send + Sync,
+{
+    /// Parallel version of [`filter_by_edge`](HGraph::filter_by_edge). The
+    /// filter is run on the edges in parallel, the nodes and any enabled
+    /// indices are then rebuilt on the calling thread.
+    pub fn par_filter_by_edge<F>(&self, filter: F) -> HGraph<NodeData, EdgeData, NodeID, EdgeID>
+    where
+        F: Fn(EdgeID) -> bool + Sync,
+    {
+        let new_edges: Vec<(EdgeID, Edge<NodeID, EdgeData>)> = self
+            .edges
+            .par_iter()
+            .filter(|(id, _)| filter(**id))
+            .map(|(id, edge)| (*id, edge.clone()))
+            .collect();
+        self.with_edges(new_edges)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{sorted, HGraph, HyperGraph};
+
+    fn sample() -> HGraph<(), u8> {
+        let mut hg = HGraph::new();
+        hg.add_nodes(8);
+        hg.add_edge([0, 1], 0);
+        hg.add_edge([0, 1, 2], 1);
+        hg.add_edge([0, 1, 2, 3], 2);
+        hg.add_edge([4, 5], 3);
+        hg.add_edge([5, 6, 7], 4);
+        hg
+    }
+
+    #[test]
+    fn matches_sequential() {
+        let hg = sample();
+        assert_eq!(sorted(hg.par_edges_of_size(2)), sorted(hg.edges_of_size(2)));
+        assert_eq!(sorted(hg.par_skeleton(3)), sorted(hg.skeleton(3)));
+        let links = hg.par_links();
+        assert_eq!(links.len(), 5);
+        for (id, link) in links {
+            assert_eq!(sorted(link), sorted(hg.link(&id)));
+        }
+    }
+
+    #[test]
+    fn filter_matches_sequential() {
+        let mut hg = sample();
+        hg.enable_inclusion_index();
+        let filter = |id: u64| id % 2 == 1;
+        let par = hg.par_filter_by_edge(filter);
+        let seq = hg.filter_by_edge(filter);
+        assert_eq!(sorted(par.edges()), sorted(seq.edges()));
+        assert_eq!(sorted(par.nodes()), sorted(seq.nodes()));
+        assert_eq!(par.get_edge(&3), Some(&3));
+        assert!(par.has_inclusion_index());
+        assert!(par.get_node(&6).is_none());
+    }
+}
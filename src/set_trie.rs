@@ -0,0 +1,283 @@
+use std::collections::BTreeMap;
+
+use fxhash::FxHashSet;
+
+use crate::{EdgeSet, HGraph, HgNode};
+
+/// A set-trie over the sorted node lists of the edges. Each edge is stored
+/// at the end of the path spelled by its nodes, so subset and superset
+/// queries only walk the branches that can still match.
+#[derive(Debug, Clone)]
+pub(crate) struct SetTrie<NodeID: HgNode, EdgeID: HgNode> {
+    root: TrieNode<NodeID, EdgeID>,
+}
+
+#[derive(Debug, Clone)]
+struct TrieNode<NodeID: HgNode, EdgeID: HgNode> {
+    children: BTreeMap<NodeID, TrieNode<NodeID, EdgeID>>,
+    /// Normally holds at most one edge, duplicates only show up while
+    /// `concatenate_nodes` is deduplicating.
+    edges: Vec<EdgeID>,
+}
+
+impl<NodeID: HgNode, EdgeID: HgNode> TrieNode<NodeID, EdgeID> {
+    fn new() -> Self {
+        TrieNode {
+            children: BTreeMap::new(),
+            edges: Vec::new(),
+        }
+    }
+
+    fn is_empty(&self) -> bool {
+        self.children.is_empty() && self.edges.is_empty()
+    }
+
+    /// Removes `edge_id` from the end of `path`, returns whether this node
+    /// can be pruned afterwards.
+    fn remove(&mut self, path: &[NodeID], edge_id: &EdgeID) -> bool {
+        match path.split_first() {
+            None => self.edges.retain(|id| id != edge_id),
+            Some((first, rest)) => {
+                if let Some(child) = self.children.get_mut(first) {
+                    if child.remove(rest, edge_id) {
+                        self.children.remove(first);
+                    }
+                }
+            }
+        }
+        self.is_empty()
+    }
+
+    fn collect_all(&self, out: &mut Vec<EdgeID>) {
+        out.extend(self.edges.iter().cloned());
+        for child in self.children.values() {
+            child.collect_all(out);
+        }
+    }
+
+    fn supersets(&self, query: &[NodeID], out: &mut Vec<EdgeID>) {
+        let Some((first, rest)) = query.split_first() else {
+            self.collect_all(out);
+            return;
+        };
+        for (label, child) in self.children.range(..=first) {
+            if label == first {
+                child.supersets(rest, out);
+            } else {
+                child.supersets(query, out);
+            }
+        }
+    }
+
+    fn subsets(&self, query: &[NodeID], out: &mut Vec<EdgeID>) {
+        out.extend(self.edges.iter().cloned());
+        for (ix, node) in query.iter().enumerate() {
+            if let Some(child) = self.children.get(node) {
+                child.subsets(&query[ix + 1..], out);
+            }
+        }
+    }
+}
+
+impl<NodeID: HgNode, EdgeID: HgNode> SetTrie<NodeID, EdgeID> {
+    fn new() -> Self {
+        SetTrie {
+            root: TrieNode::new(),
+        }
+    }
+
+    pub(crate) fn insert(&mut self, edge_id: EdgeID, nodes: &EdgeSet<NodeID>) {
+        let mut node = &mut self.root;
+        for label in nodes.0.iter() {
+            node = node.children.entry(*label).or_insert_with(TrieNode::new);
+        }
+        if !node.edges.contains(&edge_id) {
+            node.edges.push(edge_id);
+        }
+    }
+
+    pub(crate) fn remove(&mut self, edge_id: &EdgeID, nodes: &EdgeSet<NodeID>) {
+        self.root.remove(&nodes.0, edge_id);
+    }
+
+    fn supersets(&self, query: &EdgeSet<NodeID>) -> Vec<EdgeID> {
+        let mut out = Vec::new();
+        self.root.supersets(&query.0, &mut out);
+        out
+    }
+
+    fn subsets(&self, query: &EdgeSet<NodeID>) -> Vec<EdgeID> {
+        let mut out = Vec::new();
+        self.root.subsets(&query.0, &mut out);
+        out
+    }
+
+    /// Whether any edge strictly contains `nodes`, stops at the first one.
+    fn has_strict_superset(&self, nodes: &EdgeSet<NodeID>) -> bool {
+        fn search<NodeID: HgNode, EdgeID: HgNode>(
+            node: &TrieNode<NodeID, EdgeID>,
+            query: &[NodeID],
+            extra: bool,
+        ) -> bool {
+            match query.split_first() {
+                None => {
+                    // Anything below this point has more nodes than the query.
+                    (extra && !node.edges.is_empty()) || !node.children.is_empty()
+                }
+                Some((first, rest)) => node.children.range(..=first).any(|(label, child)| {
+                    if label == first {
+                        search(child, rest, extra)
+                    } else {
+                        search(child, query, true)
+                    }
+                }),
+            }
+        }
+        search(&self.root, &nodes.0, false)
+    }
+}
+
+impl<NodeData, EdgeData, NodeID: HgNode, EdgeID: HgNode>
+    HGraph<NodeData, EdgeData, NodeID, EdgeID>
+{
+    /// Builds a set-trie over the edges that answers subset and superset
+    /// queries, replacing any existing one. It is used by
+    /// [`supersets_of_nodes`](HGraph::supersets_of_nodes),
+    /// [`subsets_of_nodes`](HGraph::subsets_of_nodes) and through them the
+    /// `containing_edges` and `maximal_edges` family of the
+    /// [`HyperGraph`](crate::HyperGraph) trait, and is kept up to date through
+    /// every mutation. The index is not serialized.
+    pub fn enable_inclusion_index(&mut self) {
+        let mut index = SetTrie::new();
+        for (id, edge) in self.edges.iter() {
+            index.insert(*id, &edge.nodes);
+        }
+        self.inclusion = Some(index);
+    }
+
+    pub fn disable_inclusion_index(&mut self) {
+        self.inclusion = None;
+    }
+
+    pub fn has_inclusion_index(&self) -> bool {
+        self.inclusion.is_some()
+    }
+
+    /// Returns every edge containing all of the provided nodes, including an
+    /// edge consisting of exactly those nodes. Without the inclusion index
+    /// this intersects the containing edges of the nodes, starting with the
+    /// node in the fewest edges.
+    pub fn supersets_of_nodes(&self, nodes: impl AsRef<[NodeID]>) -> Vec<EdgeID> {
+        let query: EdgeSet<NodeID> = nodes.into();
+        if let Some(index) = &self.inclusion {
+            return index.supersets(&query);
+        }
+        if query.is_empty() {
+            return self.edges.keys().cloned().collect();
+        }
+        let mut postings = Vec::with_capacity(query.len());
+        for node in query.0.iter() {
+            match self.nodes.get(node) {
+                Some(node) => postings.push(&node.containing_edges),
+                None => return vec![],
+            }
+        }
+        postings.sort_by_key(|edges| edges.len());
+        postings[0]
+            .iter()
+            .filter(|edge_id| postings[1..].iter().all(|edges| edges.contains(edge_id)))
+            .cloned()
+            .collect()
+    }
+
+    /// Returns every edge whose nodes are all among the provided nodes,
+    /// including an edge consisting of exactly those nodes. Without the
+    /// inclusion index every edge touching one of the nodes is checked.
+    pub fn subsets_of_nodes(&self, nodes: impl AsRef<[NodeID]>) -> Vec<EdgeID> {
+        let query: EdgeSet<NodeID> = nodes.into();
+        if let Some(index) = &self.inclusion {
+            return index.subsets(&query);
+        }
+        let mut seen = FxHashSet::default();
+        let mut ret = Vec::new();
+        for node in query.0.iter() {
+            let Some(node) = self.nodes.get(node) else {
+                continue;
+            };
+            for edge_id in node.containing_edges.iter() {
+                if seen.insert(*edge_id) && query.contains(&self.edges.get(edge_id).unwrap().nodes)
+                {
+                    ret.push(*edge_id);
+                }
+            }
+        }
+        ret
+    }
+
+    /// Whether some edge strictly contains the provided nodes.
+    pub(crate) fn has_strict_superset(&self, nodes: &EdgeSet<NodeID>) -> bool {
+        if let Some(index) = &self.inclusion {
+            return index.has_strict_superset(nodes);
+        }
+        let Some(first) = nodes.get_first_node() else {
+            return !self.edges.is_empty();
+        };
+        let Some(first) = self.nodes.get(&first) else {
+            return false;
+        };
+        first.containing_edges.iter().any(|edge_id| {
+            self.edges
+                .get(edge_id)
+                .unwrap()
+                .nodes
+                .contains_strict(nodes)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{sorted, HGraph, HyperGraph};
+
+    #[test]
+    fn index_matches_scan() {
+        let mut hg = HGraph::<(), ()>::new();
+        hg.add_nodes(6);
+        let e0 = hg.add_edge([0, 1], ());
+        let e1 = hg.add_edge([0, 1, 2], ());
+        let e2 = hg.add_edge([0, 1, 3], ());
+        let e3 = hg.add_edge([0, 1, 2, 3], ());
+        let e4 = hg.add_edge([2, 4], ());
+        let e5 = hg.add_edge([1, 5], ());
+        for indexed in [false, true] {
+            if indexed {
+                hg.enable_inclusion_index();
+            }
+            assert_eq!(sorted(hg.supersets_of_nodes([0, 1])), vec![e0, e1, e2, e3]);
+            assert_eq!(sorted(hg.supersets_of_nodes([1, 3])), vec![e2, e3]);
+            assert_eq!(sorted(hg.subsets_of_nodes([0, 1, 2, 4])), vec![e0, e1, e4]);
+            assert_eq!(sorted(hg.subsets_of_nodes([1, 5])), vec![e5]);
+            assert_eq!(sorted(hg.containing_edges(&e0)), vec![e1, e2, e3]);
+            assert_eq!(hg.maximal_edges(&e0), vec![e3]);
+            assert_eq!(sorted(hg.maximal_edges_of_nodes([2])), vec![e3, e4]);
+        }
+    }
+
+    #[test]
+    fn index_follows_mutations() {
+        let mut hg = HGraph::<(), ()>::new();
+        hg.add_nodes(5);
+        hg.enable_inclusion_index();
+        let e0 = hg.add_edge([0, 1], ());
+        let e1 = hg.add_edge([0, 1, 2], ());
+        let e2 = hg.add_edge([3, 4], ());
+        hg.remove_edge(e1);
+        assert_eq!(hg.supersets_of_nodes([0, 1]), vec![e0]);
+        hg.remove_node(4);
+        assert_eq!(sorted(hg.subsets_of_nodes([0, 1, 3])), vec![e0, e2]);
+        hg.concatenate_nodes(&3, &0);
+        assert!(hg.supersets_of_nodes([3]).is_empty());
+        assert_eq!(hg.subsets_of_nodes([0, 1]), vec![e0]);
+        assert_eq!(hg.maximal_edges_of_nodes([0]), vec![e0]);
+    }
+}
@@ -0,0 +1,239 @@
+use fxhash::{FxHashMap, FxHashSet};
+
+use crate::{EdgeSet, HGraph, HgNode};
+
+/// A MinHash signature for every edge split into bands for locality
+/// sensitive hashing. Two edges land in the same bucket of a band if their
+/// signatures agree on every row of that band, which happens with
+/// probability `J^rows` for edges of Jaccard similarity `J`.
+#[derive(Debug, Clone)]
+pub(crate) struct SimilarityIndex<EdgeID: HgNode> {
+    pub(crate) bands: usize,
+    pub(crate) rows: usize,
+    signatures: FxHashMap<EdgeID, Vec<u64>>,
+    buckets: FxHashMap<(usize, u64), FxHashSet<EdgeID>>,
+}
+
+impl<EdgeID: HgNode> SimilarityIndex<EdgeID> {
+    fn new(bands: usize, rows: usize) -> Self {
+        SimilarityIndex {
+            bands: bands.max(1),
+            rows: rows.max(1),
+            signatures: FxHashMap::default(),
+            buckets: FxHashMap::default(),
+        }
+    }
+
+    fn signature<NodeID: HgNode>(&self, nodes: &[NodeID]) -> Vec<u64> {
+        (0..self.bands * self.rows)
+            .map(|seed| {
+                nodes
+                    .iter()
+                    .map(|node| fxhash::hash64(&(seed, node)))
+                    .min()
+                    .unwrap_or(u64::MAX)
+            })
+            .collect()
+    }
+
+    fn band_keys(&self, signature: &[u64]) -> Vec<(usize, u64)> {
+        signature
+            .chunks(self.rows)
+            .enumerate()
+            .map(|(band, rows)| (band, fxhash::hash64(rows)))
+            .collect()
+    }
+
+    pub(crate) fn insert<NodeID: HgNode>(&mut self, edge_id: EdgeID, nodes: &EdgeSet<NodeID>) {
+        let signature = self.signature(&nodes.0);
+        for key in self.band_keys(&signature) {
+            self.buckets.entry(key).or_default().insert(edge_id);
+        }
+        self.signatures.insert(edge_id, signature);
+    }
+
+    pub(crate) fn remove(&mut self, edge_id: &EdgeID) {
+        if let Some(signature) = self.signatures.remove(edge_id) {
+            for key in self.band_keys(&signature) {
+                if let Some(bucket) = self.buckets.get_mut(&key) {
+                    bucket.remove(edge_id);
+                    if bucket.is_empty() {
+                        self.buckets.remove(&key);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Every edge sharing at least one bucket with `nodes`.
+    fn candidates<NodeID: HgNode>(&self, nodes: &[NodeID]) -> FxHashSet<EdgeID> {
+        let signature = self.signature(nodes);
+        let mut candidates = FxHashSet::default();
+        for key in self.band_keys(&signature) {
+            if let Some(bucket) = self.buckets.get(&key) {
+                candidates.extend(bucket.iter().cloned());
+            }
+        }
+        candidates
+    }
+}
+
+impl<NodeData, EdgeData, NodeID: HgNode, EdgeID: HgNode>
+    HGraph<NodeData, EdgeData, NodeID, EdgeID>
+{
+    /// Builds a MinHash/LSH index over the edges using `bands` bands of
+    /// `rows` hashes each, replacing any existing index. The index is kept up
+    /// to date as edges are added and removed and is used by
+    /// [`similar_edges`](HGraph::similar_edges) and
+    /// [`top_k_similar_edges`](HGraph::top_k_similar_edges). Pairs with
+    /// Jaccard similarity above roughly `(1 / bands)^(1 / rows)` are likely to
+    /// be found. The index is not serialized.
+    pub fn enable_similarity_index(&mut self, bands: usize, rows: usize) {
+        let mut index = SimilarityIndex::new(bands, rows);
+        for (id, edge) in self.edges.iter() {
+            index.insert(*id, &edge.nodes);
+        }
+        self.similarity = Some(index);
+    }
+
+    pub fn disable_similarity_index(&mut self) {
+        self.similarity = None;
+    }
+
+    pub fn has_similarity_index(&self) -> bool {
+        self.similarity.is_some()
+    }
+
+    /// Returns the edges with Jaccard similarity at least `jaccard_threshold`
+    /// to the provided nodes, along with their similarity, sorted from most to
+    /// least similar. An edge with exactly the provided nodes is included.
+    /// With a similarity index enabled only edges sharing an LSH bucket with
+    /// the query are checked, so a few matches may be missed, otherwise every
+    /// edge sharing a node with the query is checked exactly.
+    pub fn similar_edges(
+        &self,
+        nodes: impl AsRef<[NodeID]>,
+        jaccard_threshold: f64,
+    ) -> Vec<(EdgeID, f64)> {
+        let query: EdgeSet<NodeID> = nodes.into();
+        if query.is_empty() {
+            return vec![];
+        }
+        let candidates = match &self.similarity {
+            Some(index) => index.candidates(&query.0),
+            None => self.edges_sharing_nodes(&query),
+        };
+        let mut similar: Vec<(EdgeID, f64)> = candidates
+            .into_iter()
+            .filter_map(|id| {
+                let similarity = jaccard(&query, &self.edges.get(&id)?.nodes);
+                (similarity >= jaccard_threshold).then_some((id, similarity))
+            })
+            .collect();
+        sort_by_similarity(&mut similar);
+        similar
+    }
+
+    /// Returns up to `k` other edges with the highest Jaccard similarity to
+    /// `edge_id`, most similar first. Edges sharing an LSH bucket are
+    /// preferred when the similarity index is enabled, if there are fewer
+    /// than `k` of them every edge sharing a node is considered as well.
+    pub fn top_k_similar_edges(&self, edge_id: &EdgeID, k: usize) -> Vec<(EdgeID, f64)> {
+        let Some(edge) = self.edges.get(edge_id) else {
+            return vec![];
+        };
+        let mut candidates = match &self.similarity {
+            Some(index) => index.candidates(&edge.nodes.0),
+            None => FxHashSet::default(),
+        };
+        candidates.remove(edge_id);
+        if candidates.len() < k {
+            candidates.extend(self.edges_sharing_nodes(&edge.nodes));
+            candidates.remove(edge_id);
+        }
+        let mut similar: Vec<(EdgeID, f64)> = candidates
+            .into_iter()
+            .map(|id| {
+                (
+                    id,
+                    jaccard(&edge.nodes, &self.edges.get(&id).unwrap().nodes),
+                )
+            })
+            .collect();
+        sort_by_similarity(&mut similar);
+        similar.truncate(k);
+        similar
+    }
+
+    fn edges_sharing_nodes(&self, nodes: &EdgeSet<NodeID>) -> FxHashSet<EdgeID> {
+        let mut edges = FxHashSet::default();
+        for node in nodes.0.iter() {
+            if let Some(node) = self.nodes.get(node) {
+                edges.extend(node.containing_edges.iter().cloned());
+            }
+        }
+        edges
+    }
+}
+
+fn jaccard<NodeID: HgNode>(a: &EdgeSet<NodeID>, b: &EdgeSet<NodeID>) -> f64 {
+    let shared = a.intersection(b).len();
+    shared as f64 / (a.len() + b.len() - shared) as f64
+}
+
+fn sort_by_similarity<EdgeID: HgNode>(similar: &mut [(EdgeID, f64)]) {
+    similar.sort_by(|a, b| b.1.total_cmp(&a.1).then(a.0.cmp(&b.0)));
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::HGraph;
+
+    fn baskets() -> HGraph<(), ()> {
+        let mut hg = HGraph::<(), ()>::new();
+        hg.add_nodes(12);
+        hg.add_edge([0, 1, 2, 3, 4, 5], ());
+        hg.add_edge([0, 1, 2, 3, 4, 6], ());
+        hg.add_edge([0, 1, 2, 7, 8, 9], ());
+        hg.add_edge([9, 10, 11], ());
+        hg
+    }
+
+    #[test]
+    fn exact_and_indexed_queries() {
+        let mut hg = baskets();
+        let exact = hg.similar_edges([0, 1, 2, 3, 4, 5], 0.5);
+        assert_eq!(exact, vec![(0, 1.0), (1, 5.0 / 7.0)]);
+        assert_eq!(
+            hg.top_k_similar_edges(&0, 2),
+            vec![(1, 5.0 / 7.0), (2, 1.0 / 3.0)]
+        );
+
+        hg.enable_similarity_index(20, 2);
+        let indexed = hg.similar_edges([0, 1, 2, 3, 4, 5], 0.5);
+        assert_eq!(indexed, exact);
+        assert_eq!(hg.top_k_similar_edges(&0, 1), vec![(1, 5.0 / 7.0)]);
+        assert!(hg.similar_edges([10, 11], 0.5).contains(&(3, 2.0 / 3.0)));
+    }
+
+    #[test]
+    fn index_follows_mutations() {
+        let mut hg = baskets();
+        hg.enable_similarity_index(20, 2);
+        hg.remove_edge(1);
+        assert_eq!(hg.similar_edges([0, 1, 2, 3, 4, 6], 0.9), vec![]);
+        let new_edge = hg.add_edge([0, 1, 2, 3, 4, 6], ());
+        assert_eq!(
+            hg.similar_edges([0, 1, 2, 3, 4, 6], 0.9),
+            vec![(new_edge, 1.0)]
+        );
+        hg.remove_node(5);
+        assert_eq!(hg.similar_edges([0, 1, 2, 3, 4], 1.0), vec![(0, 1.0)]);
+
+        let cloned = hg.clone();
+        assert!(cloned.has_similarity_index());
+        let json = serde_json::to_string(&hg).unwrap();
+        let back: HGraph<(), ()> = serde_json::from_str(&json).unwrap();
+        assert!(!back.has_similarity_index());
+    }
+}
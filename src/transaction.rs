@@ -0,0 +1,443 @@
+use fxhash::{FxHashMap, FxHashSet};
+
+use crate::hgraph::{Edge, Node};
+use crate::id_policy::IdAllocator;
+use crate::set_trie::SetTrie;
+use crate::similarity::SimilarityIndex;
+use crate::{EdgeSet, HGraph, HgNode};
+
+/// What has to be done to take back one applied operation.
+enum Undo<NodeData, EdgeData, NodeID: HgNode, EdgeID: HgNode> {
+    AddedNode(NodeID),
+    AddedEdge(EdgeID),
+    RemovedEdge(EdgeID, Edge<NodeID, EdgeData>),
+    /// The node along with the edges it was dropped from.
+    RemovedNode(NodeID, NodeData, Vec<EdgeID>),
+    NodeData(NodeID, NodeData),
+    EdgeData(EdgeID, EdgeData),
+}
+
+/// A batch of mutations to an [`HGraph`], see
+/// [`transaction`](HGraph::transaction). Operations are applied as they are
+/// made, so reads through [`graph`](Transaction::graph) see them, but the
+/// edge indexes are only brought up to date once the batch commits.
+/// Dropping a transaction that has not committed rolls it back, which also
+/// covers a batch that panics.
+pub struct Transaction<'a, NodeData, EdgeData, NodeID: HgNode, EdgeID: HgNode> {
+    graph: &'a mut HGraph<NodeData, EdgeData, NodeID, EdgeID>,
+    /// Taken on commit.
+    detached: Option<Detached<NodeID, EdgeID>>,
+    undo: Vec<Undo<NodeData, EdgeData, NodeID, EdgeID>>,
+    /// The nodes each changed edge had before the batch, `None` for edges
+    /// added in it.
+    touched: FxHashMap<EdgeID, Option<EdgeSet<NodeID>>>,
+    error: Option<String>,
+}
+
+/// Everything a transaction restores on rollback or hands back on commit.
+struct Detached<NodeID: HgNode, EdgeID: HgNode> {
+    next_node_id: NodeID,
+    next_edge_id: EdgeID,
+    node_alloc: IdAllocator<NodeID>,
+    edge_alloc: IdAllocator<EdgeID>,
+    similarity: Option<SimilarityIndex<EdgeID>>,
+    inclusion: Option<SetTrie<NodeID, EdgeID>>,
+    facets: Option<FxHashSet<EdgeID>>,
+}
+
+impl<NodeData, EdgeData, NodeID: HgNode, EdgeID: HgNode>
+    HGraph<NodeData, EdgeData, NodeID, EdgeID>
+{
+    /// Runs `batch` against a [`Transaction`] and applies all of its
+    /// operations atomically. If an operation fails or `batch` returns an
+    /// error every operation is rolled back, leaving the graph, its ID
+    /// counters and its indexes exactly as before, and the first error is
+    /// returned. The similarity, inclusion and facet indexes are updated once
+    /// for the whole batch on commit.
+    /// ```
+    /// use mhgl::HGraph;
+    /// let mut hg = HGraph::<&str, ()>::new();
+    /// let a = hg.add_node("a");
+    /// let result = hg.transaction(|tx| {
+    ///     let b = tx.add_node("b")?;
+    ///     tx.add_edge([a, b], ())?;
+    ///     tx.add_edge([a, 7], ())
+    /// });
+    /// assert!(result.is_err());
+    /// assert_eq!(hg.num_nodes(), 1);
+    /// ```
+    pub fn transaction<T, F>(&mut self, batch: F) -> Result<T, String>
+    where
+        F: FnOnce(&mut Transaction<'_, NodeData, EdgeData, NodeID, EdgeID>) -> Result<T, String>,
+    {
+        let detached = Detached {
+            next_node_id: self.next_node_id,
+            next_edge_id: self.next_edge_id,
+            node_alloc: self.node_alloc.clone(),
+            edge_alloc: self.edge_alloc.clone(),
+            similarity: self.similarity.take(),
+            inclusion: self.inclusion.take(),
+            facets: self.facets.take(),
+        };
+        self.observers.hold();
+        let mut tx = Transaction {
+            graph: self,
+            detached: Some(detached),
+            undo: Vec::new(),
+            touched: FxHashMap::default(),
+            error: None,
+        };
+        let result = batch(&mut tx);
+        let result = match tx.error.take() {
+            Some(error) => Err(error),
+            None => result,
+        };
+        if result.is_ok() {
+            tx.commit();
+        }
+        drop(tx);
+        result
+    }
+
+    fn commit(
+        &mut self,
+        detached: Detached<NodeID, EdgeID>,
+        touched: FxHashMap<EdgeID, Option<EdgeSet<NodeID>>>,
+    ) {
+        self.similarity = detached.similarity;
+        self.inclusion = detached.inclusion;
+        for (id, old_nodes) in touched.iter() {
+            let new_nodes = self.edges.get(id).map(|edge| &edge.nodes);
+            if let Some(index) = self.similarity.as_mut() {
+                index.remove(id);
+                if let Some(nodes) = new_nodes {
+                    index.insert(*id, nodes);
+                }
+            }
+            if let Some(index) = self.inclusion.as_mut() {
+                if let Some(nodes) = old_nodes {
+                    index.remove(id, nodes);
+                }
+                if let Some(nodes) = new_nodes {
+                    index.insert(*id, nodes);
+                }
+            }
+        }
+        let Some(mut facets) = detached.facets else {
+            return;
+        };
+        // Only the changed edges and the edges inside their old or new nodes
+        // can have gained or lost a strict superset.
+        let mut candidates: FxHashSet<EdgeID> = touched.keys().cloned().collect();
+        for (id, old_nodes) in touched.iter() {
+            if let Some(nodes) = old_nodes {
+                candidates.extend(self.subsets_of_nodes(&nodes.0));
+            }
+            if let Some(edge) = self.edges.get(id) {
+                candidates.extend(self.subsets_of_nodes(&edge.nodes.0));
+            }
+        }
+        for id in candidates {
+            facets.remove(&id);
+            if let Some(edge) = self.edges.get(&id) {
+                if !self.has_strict_superset(&edge.nodes) {
+                    facets.insert(id);
+                }
+            }
+        }
+        self.facets = Some(facets);
+    }
+
+    fn rollback(
+        &mut self,
+        detached: Detached<NodeID, EdgeID>,
+        undo: Vec<Undo<NodeData, EdgeData, NodeID, EdgeID>>,
+    ) {
+        for op in undo.into_iter().rev() {
+            match op {
+                Undo::AddedNode(id) => {
+                    self.nodes.remove(&id);
+                }
+                Undo::AddedEdge(id) => {
+                    self.remove_edge(id);
+                }
+                Undo::RemovedEdge(id, edge) => {
+                    for node in edge.nodes.0.iter() {
+                        let node = self
+                            .nodes
+                            .get_mut(node)
+                            .expect("Node of removed edge missing.");
+                        node.containing_edges.insert(id);
+                    }
+                    self.edges.insert(id, edge);
+                }
+                Undo::RemovedNode(id, data, edges) => {
+                    let mut node = Node::new(data);
+                    for edge_id in edges {
+                        let edge = self.edges.get_mut(&edge_id).expect("Shrunk edge missing.");
+                        edge.nodes.add_node(id);
+                        node.containing_edges.insert(edge_id);
+                    }
+                    self.nodes.insert(id, node);
+                }
+                Undo::NodeData(id, data) => {
+                    self.insert_node_data(&id, data);
+                }
+                Undo::EdgeData(id, data) => {
+                    self.insert_edge_data(&id, data);
+                }
+            }
+        }
+        self.next_node_id = detached.next_node_id;
+        self.next_edge_id = detached.next_edge_id;
+        self.node_alloc = detached.node_alloc;
+        self.edge_alloc = detached.edge_alloc;
+        self.similarity = detached.similarity;
+        self.inclusion = detached.inclusion;
+        self.facets = detached.facets;
+    }
+}
+
+impl<NodeData, EdgeData, NodeID: HgNode, EdgeID: HgNode>
+    Transaction<'_, NodeData, EdgeData, NodeID, EdgeID>
+{
+    /// The graph with every operation so far applied. The edge indexes are
+    /// detached until the batch commits, so queries that would use them
+    /// look at the edges directly instead.
+    pub fn graph(&self) -> &HGraph<NodeData, EdgeData, NodeID, EdgeID> {
+        self.graph
+    }
+
+    /// Adds a node, fails if the `NodeID` type is out of space.
+    pub fn add_node(&mut self, data: NodeData) -> Result<NodeID, String> {
+        if self.graph.next_node_id == NodeID::max_number() && !self.graph.node_alloc.can_reuse() {
+            return self.fail("The storage type for NodeIDs ran out of space.".to_string());
+        }
+        let id = self.graph.add_node(data);
+        self.undo.push(Undo::AddedNode(id));
+        Ok(id)
+    }
+
+    /// Adds an edge, or replaces the data of the edge with the same nodes.
+    /// Fails if a node is not present or the `EdgeID` type is out of space.
+    pub fn add_edge(
+        &mut self,
+        nodes: impl AsRef<[NodeID]>,
+        data: EdgeData,
+    ) -> Result<EdgeID, String> {
+        let nodes = nodes.as_ref();
+        if let Some(id) = self.graph.find_id(nodes) {
+            let old = self.graph.insert_edge_data(&id, data).unwrap();
+            self.undo.push(Undo::EdgeData(id, old));
+            return Ok(id);
+        }
+        if let Some(missing) = nodes
+            .iter()
+            .find(|node| !self.graph.nodes.contains_key(node))
+        {
+            return self.fail(format!(
+                "Node {:?} is not present in the hypergraph.",
+                missing
+            ));
+        }
+        if self.graph.next_edge_id == EdgeID::max_number() && !self.graph.edge_alloc.can_reuse() {
+            return self
+                .fail("Ran out of edges, need to use a bigger EdgeID representation.".to_string());
+        }
+        let id = self.graph.add_edge(nodes, data);
+        self.touched.entry(id).or_insert(None);
+        self.undo.push(Undo::AddedEdge(id));
+        Ok(id)
+    }
+
+    /// Removes the node, shrinking the edges containing it and removing the
+    /// ones left empty. Fails if the node is not present.
+    pub fn remove_node(&mut self, node: NodeID) -> Result<(), String> {
+        let Some(removed) = self.graph.nodes.get(&node) else {
+            return self.fail(format!("Node {:?} is not present in the hypergraph.", node));
+        };
+        let (emptied, shrunk): (Vec<EdgeID>, Vec<EdgeID>) = removed
+            .containing_edges
+            .iter()
+            .partition(|id| self.graph.edges.get(id).unwrap().nodes.len() == 1);
+        for id in emptied {
+            self.remove_edge(id)?;
+        }
+        for id in shrunk.iter() {
+            self.touch(*id);
+        }
+        let data = self.graph.remove_node(node).unwrap();
+        self.undo.push(Undo::RemovedNode(node, data, shrunk));
+        Ok(())
+    }
+
+    /// Removes the edge, fails if it is not present.
+    pub fn remove_edge(&mut self, edge: EdgeID) -> Result<(), String> {
+        let Some(removed) = self.graph.edges.get(&edge) else {
+            return self.fail(format!("Edge {:?} is not present in the hypergraph.", edge));
+        };
+        let nodes = removed.nodes.clone();
+        self.touch(edge);
+        let data = self.graph.remove_edge(edge).unwrap();
+        self.undo
+            .push(Undo::RemovedEdge(edge, Edge { nodes, data }));
+        Ok(())
+    }
+
+    /// Replaces the data of the node, fails if it is not present.
+    pub fn set_node_data(&mut self, node: &NodeID, data: NodeData) -> Result<(), String> {
+        match self.graph.insert_node_data(node, data) {
+            Some(old) => {
+                self.undo.push(Undo::NodeData(*node, old));
+                Ok(())
+            }
+            None => self.fail(format!("Node {:?} is not present in the hypergraph.", node)),
+        }
+    }
+
+    /// Replaces the data of the edge, fails if it is not present.
+    pub fn set_edge_data(&mut self, edge: &EdgeID, data: EdgeData) -> Result<(), String> {
+        match self.graph.insert_edge_data(edge, data) {
+            Some(old) => {
+                self.undo.push(Undo::EdgeData(*edge, old));
+                Ok(())
+            }
+            None => self.fail(format!("Edge {:?} is not present in the hypergraph.", edge)),
+        }
+    }
+
+    fn commit(&mut self) {
+        if let Some(detached) = self.detached.take() {
+            let touched = std::mem::take(&mut self.touched);
+            self.graph.commit(detached, touched);
+            self.graph.observers.release(true);
+        }
+    }
+
+    /// Remembers the nodes the edge had before its first change.
+    fn touch(&mut self, edge: EdgeID) {
+        if !self.touched.contains_key(&edge) {
+            let nodes = self.graph.edges.get(&edge).map(|edge| edge.nodes.clone());
+            self.touched.insert(edge, nodes);
+        }
+    }
+
+    /// Records the error so the batch rolls back even if the caller ignores
+    /// it.
+    fn fail<T>(&mut self, error: String) -> Result<T, String> {
+        if self.error.is_none() {
+            self.error = Some(error.clone());
+        }
+        Err(error)
+    }
+}
+
+impl<NodeData, EdgeData, NodeID: HgNode, EdgeID: HgNode> Drop
+    for Transaction<'_, NodeData, EdgeData, NodeID, EdgeID>
+{
+    fn drop(&mut self) {
+        if let Some(detached) = self.detached.take() {
+            let undo = std::mem::take(&mut self.undo);
+            self.graph.rollback(detached, undo);
+            // Also runs when the batch panics, so subscribers are not left
+            // waiting for a commit that never comes.
+            self.graph.observers.release(false);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::panic::{catch_unwind, AssertUnwindSafe};
+
+    use crate::{sorted, HGraph, HyperGraph};
+
+    #[test]
+    fn failed_batch_rolls_back() {
+        let mut hg = HGraph::<u8, &str>::new();
+        hg.add_nodes(3);
+        let e0 = hg.add_edge([0, 1], "a");
+        let e1 = hg.add_edge([2], "b");
+        let e2 = hg.add_edge([0, 1, 2], "c");
+        hg.enable_inclusion_index();
+        hg.enable_facet_tracking();
+        let before = format!("{:?}", (sorted(hg.edges()), sorted(hg.facets())));
+
+        let result: Result<(), String> = hg.transaction(|tx| {
+            let n3 = tx.add_node(3)?;
+            tx.add_edge([0, n3], "d")?;
+            tx.remove_node(2)?;
+            tx.set_edge_data(&e0, "changed")?;
+            tx.add_edge([0, 1], "replaced")?;
+            // The error is ignored but still rolls the batch back.
+            let _ = tx.remove_edge(42);
+            Ok(())
+        });
+        assert!(result.is_err());
+        assert_eq!(
+            before,
+            format!("{:?}", (sorted(hg.edges()), sorted(hg.facets())))
+        );
+        assert_eq!(hg.get_edge(&e0), Some(&"a"));
+        assert_eq!(hg.query_edge(&e1), Some(vec![2]));
+        assert_eq!(hg.query_edge(&e2), Some(vec![0, 1, 2]));
+        assert_eq!(sorted(hg.incident_edges(&2)), vec![e1, e2]);
+        assert_eq!(sorted(hg.supersets_of_nodes([2])), vec![e1, e2]);
+        assert_eq!(hg.add_node(3), 3);
+        assert_eq!(hg.add_edge([1, 3], "e"), 3);
+    }
+
+    #[test]
+    fn panicking_batch_rolls_back() {
+        let mut hg = HGraph::<(), ()>::new();
+        hg.add_nodes(2);
+        hg.add_edge([0, 1], ());
+        hg.enable_inclusion_index();
+        let outcome = catch_unwind(AssertUnwindSafe(|| {
+            hg.transaction::<(), _>(|tx| {
+                tx.add_edge([0], ())?;
+                panic!("batch failed")
+            })
+        }));
+        assert!(outcome.is_err());
+        assert_eq!(hg.num_edges(), 1);
+        assert!(hg.has_inclusion_index());
+        assert_eq!(hg.supersets_of_nodes([0]), vec![0]);
+        assert_eq!(hg.add_edge([0], ()), 1);
+    }
+
+    #[test]
+    fn committed_batch_updates_indexes() {
+        let mut hg = HGraph::<(), u8>::new();
+        hg.add_nodes(4);
+        let e0 = hg.add_edge([0, 1], 0);
+        let e1 = hg.add_edge([0, 1, 2], 1);
+        hg.enable_inclusion_index();
+        hg.enable_similarity_index(4, 2);
+        hg.enable_facet_tracking();
+
+        let e2 = hg
+            .transaction(|tx| {
+                tx.remove_node(2)?;
+                let e2 = tx.add_edge([1, 3], 2)?;
+                tx.set_node_data(&3, ())?;
+                Ok(e2)
+            })
+            .unwrap();
+        assert_eq!(hg.query_edge(&e1), Some(vec![0, 1]));
+        assert_eq!(sorted(hg.facets()), vec![e0, e1, e2]);
+        assert_eq!(sorted(hg.supersets_of_nodes([1])), vec![e0, e1, e2]);
+        assert!(hg.supersets_of_nodes([2]).is_empty());
+        assert!(hg
+            .similar_edges([1, 3], 0.9)
+            .iter()
+            .any(|(id, _)| *id == e2));
+
+        hg.transaction(|tx| tx.remove_edge(e2)).unwrap();
+        hg.transaction(|tx| tx.remove_edge(e1)).unwrap();
+        assert_eq!(hg.facets(), vec![e0]);
+        let mut fresh = hg.clone();
+        fresh.disable_facet_tracking();
+        assert_eq!(fresh.facets(), hg.facets());
+    }
+}
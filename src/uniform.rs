@@ -0,0 +1,520 @@
+use std::collections::HashMap;
+
+use fxhash::{FxHashMap, FxHashSet};
+
+use crate::hgraph::Node;
+use crate::id_map::IdMap;
+use crate::{HGraph, HgNode, HyperGraph};
+
+/// A k-uniform hypergraph, every edge has exactly `K` nodes. Edges are stored
+/// as sorted `[NodeID; K]` arrays along with a lookup from the array to the
+/// edge ID, so finding an edge is a single hash. Edges of any other size are
+/// rejected when added, and removing a node removes every edge containing it
+/// instead of shrinking them.
+#[derive(Debug, Clone)]
+pub struct UniformHGraph<
+    const K: usize,
+    NodeData,
+    EdgeData,
+    NodeID: HgNode = u32,
+    EdgeID: HgNode = u64,
+> {
+    next_node_id: NodeID,
+    next_edge_id: EdgeID,
+    nodes: IdMap<NodeID, Node<NodeData, EdgeID>>,
+    edges: IdMap<EdgeID, ([NodeID; K], EdgeData)>,
+    edge_lookup: FxHashMap<[NodeID; K], EdgeID>,
+}
+
+impl<const K: usize, N, E, NodeID: HgNode, EdgeID: HgNode> UniformHGraph<K, N, E, NodeID, EdgeID>
+where
+    N: Default,
+{
+    pub fn add_nodes(&mut self, num_nodes: usize) -> Vec<NodeID> {
+        (0..num_nodes)
+            .map(|_| self.add_node(N::default()))
+            .collect()
+    }
+}
+
+impl<const K: usize, NodeData, EdgeData, NodeID: HgNode, EdgeID: HgNode> Default
+    for UniformHGraph<K, NodeData, EdgeData, NodeID, EdgeID>
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<const K: usize, NodeData, EdgeData, NodeID: HgNode, EdgeID: HgNode>
+    UniformHGraph<K, NodeData, EdgeData, NodeID, EdgeID>
+{
+    /// `panic`s if `K` is zero.
+    pub fn new() -> Self {
+        assert!(
+            K > 0,
+            "A uniform hypergraph needs edges of at least one node."
+        );
+        Self {
+            next_node_id: NodeID::zero(),
+            next_edge_id: EdgeID::zero(),
+            nodes: IdMap::default(),
+            edges: IdMap::default(),
+            edge_lookup: FxHashMap::default(),
+        }
+    }
+
+    /// Returns the new id if a node can be added, `panic`s if the graph
+    /// is out of space to add new nodes.
+    pub fn add_node(&mut self, node: NodeData) -> NodeID {
+        let node_id = self.next_node_id;
+        if self.next_node_id == NodeID::max_number() {
+            panic!("The storage type for NodeIDs ran out of space.")
+        }
+        self.next_node_id.plus_one();
+        self.nodes.insert(node_id, Node::new(node));
+        node_id
+    }
+
+    /// Creates an edge from the provided nodes, if the edge already exists
+    /// the old data is replaced. Returns an error if there are not exactly
+    /// `K` distinct nodes or if a node is not present in the hypergraph.
+    /// `panic`s if you create more edges than allowable by the `EdgeID`
+    /// storage type.
+    pub fn add_edge(
+        &mut self,
+        nodes: impl AsRef<[NodeID]>,
+        data: EdgeData,
+    ) -> Result<EdgeID, String> {
+        let key = self.edge_key(nodes.as_ref())?;
+        if let Some(missing) = key.iter().find(|node| !self.nodes.contains_key(node)) {
+            return Err(format!(
+                "Node {:?} is not present in the hypergraph.",
+                missing
+            ));
+        }
+        if let Some(id) = self.edge_lookup.get(&key) {
+            self.edges.get_mut(id).unwrap().1 = data;
+            return Ok(*id);
+        }
+
+        let id = self.next_edge_id;
+        if self.next_edge_id == EdgeID::max_number() {
+            panic!("Ran out of edges, need to use a bigger EdgeID representation.")
+        }
+        self.next_edge_id.plus_one();
+        for node in key.iter() {
+            self.nodes
+                .get_mut(node)
+                .unwrap()
+                .containing_edges
+                .insert(id);
+        }
+        self.edges.insert(id, (key, data));
+        self.edge_lookup.insert(key, id);
+        Ok(id)
+    }
+
+    /// Removes the node and every edge containing it, as the edges cannot
+    /// shrink without breaking uniformity.
+    pub fn remove_node(&mut self, node: NodeID) -> Option<NodeData> {
+        let removed = self.nodes.remove(&node)?;
+        for edge_id in removed.containing_edges.iter() {
+            self.remove_edge(*edge_id);
+        }
+        Some(removed.data)
+    }
+
+    /// Returns the `EdgeData` of the associated edge if it existed and `None`
+    /// if an incorrect edge was provided.
+    pub fn remove_edge(&mut self, edge_id: EdgeID) -> Option<EdgeData> {
+        let (key, data) = self.edges.remove(&edge_id)?;
+        self.edge_lookup.remove(&key);
+        for node in key.iter() {
+            if let Some(node) = self.nodes.get_mut(node) {
+                node.containing_edges.remove(&edge_id);
+            }
+        }
+        Some(data)
+    }
+
+    pub fn get_node(&self, node: &NodeID) -> Option<&NodeData> {
+        self.nodes.get(node).map(|node| &node.data)
+    }
+
+    pub fn get_node_mut(&mut self, node: &NodeID) -> Option<&mut NodeData> {
+        self.nodes.get_mut(node).map(|node| &mut node.data)
+    }
+
+    pub fn get_edge(&self, edge: &EdgeID) -> Option<&EdgeData> {
+        self.edges.get(edge).map(|(_, data)| data)
+    }
+
+    pub fn get_edge_mut(&mut self, edge: &EdgeID) -> Option<&mut EdgeData> {
+        self.edges.get_mut(edge).map(|(_, data)| data)
+    }
+
+    /// The sorted nodes of the edge.
+    pub fn edge_nodes(&self, edge: &EdgeID) -> Option<&[NodeID; K]> {
+        self.edges.get(edge).map(|(nodes, _)| nodes)
+    }
+
+    /// Returns the ID of the edge consisting of exactly the provided nodes, if
+    /// there is one.
+    pub fn find_id(&self, nodes: impl AsRef<[NodeID]>) -> Option<EdgeID> {
+        let key = self.edge_key(nodes.as_ref()).ok()?;
+        self.edge_lookup.get(&key).cloned()
+    }
+
+    /// The sorted array of the provided nodes, an error if there are not
+    /// exactly `K` distinct nodes.
+    fn edge_key(&self, nodes: &[NodeID]) -> Result<[NodeID; K], String> {
+        let mut nodes = nodes.to_vec();
+        nodes.sort();
+        nodes.dedup();
+        let len = nodes.len();
+        nodes
+            .try_into()
+            .map_err(|_| format!("Expected an edge of {} distinct nodes, found {}.", K, len))
+    }
+
+    /// The IDs of the edges containing every one of the provided sorted,
+    /// deduplicated nodes, found by scanning the edges of the node of
+    /// smallest degree.
+    fn supersets(&self, query: &[NodeID]) -> Vec<EdgeID> {
+        let Some(query_nodes) = query
+            .iter()
+            .map(|node| self.nodes.get(node))
+            .collect::<Option<Vec<_>>>()
+        else {
+            return Vec::new();
+        };
+        let rarest = query_nodes
+            .into_iter()
+            .min_by_key(|node| node.containing_edges.len());
+        match rarest {
+            Some(node) => node
+                .containing_edges
+                .iter()
+                .filter(|id| {
+                    let (nodes, _) = self.edges.get(id).unwrap();
+                    query.iter().all(|node| nodes.binary_search(node).is_ok())
+                })
+                .cloned()
+                .collect(),
+            None => self.edges.keys().cloned().collect(),
+        }
+    }
+
+    /// The IDs of the edges whose nodes are all in `query`.
+    fn subsets(&self, query: &FxHashSet<NodeID>) -> Vec<EdgeID> {
+        let mut subsets = FxHashSet::default();
+        for node in query.iter().filter_map(|node| self.nodes.get(node)) {
+            for id in node.containing_edges.iter() {
+                let (nodes, _) = self.edges.get(id).unwrap();
+                if nodes.iter().all(|node| query.contains(node)) {
+                    subsets.insert(*id);
+                }
+            }
+        }
+        subsets.into_iter().collect()
+    }
+
+    /// Contracts the adjacency tensor of the hypergraph with `x` along all
+    /// but its first mode, giving the vector `A·x^{k-1}` used for H- and
+    /// Z-eigenvalues. With the tensor normalized by `1 / (k - 1)!` each edge
+    /// contributes once, so entry `i` is the sum over the edges `e` containing
+    /// `i` of the product of `x_j` for the other nodes `j` of `e`. Nodes
+    /// missing from `x` are treated as zero and every node has an entry in
+    /// the output.
+    pub fn tensor_contraction(&self, x: &HashMap<NodeID, f64>) -> HashMap<NodeID, f64> {
+        let mut out: HashMap<NodeID, f64> = self.nodes.keys().map(|node| (*node, 0.0)).collect();
+        for (nodes, _) in self.edges.values() {
+            for (ix, node) in nodes.iter().enumerate() {
+                let product: f64 = nodes
+                    .iter()
+                    .enumerate()
+                    .filter(|(jx, _)| *jx != ix)
+                    .map(|(_, other)| x.get(other).cloned().unwrap_or(0.0))
+                    .product();
+                *out.get_mut(node).unwrap() += product;
+            }
+        }
+        out
+    }
+
+    /// The shadow of the hypergraph, every `K - 1` node subset of an edge,
+    /// sorted and without duplicates. Returned as `Vec`s as stable Rust
+    /// cannot yet name the `[NodeID; K - 1]` array type.
+    pub fn shadow(&self) -> Vec<Vec<NodeID>> {
+        let mut shadow = FxHashSet::default();
+        for (nodes, _) in self.edges.values() {
+            for ix in 0..K {
+                let mut face = nodes.to_vec();
+                face.remove(ix);
+                shadow.insert(face);
+            }
+        }
+        let mut shadow: Vec<Vec<NodeID>> = shadow.into_iter().collect();
+        shadow.sort();
+        shadow
+    }
+}
+
+impl<const K: usize, NodeData, EdgeData, NodeID, EdgeID>
+    UniformHGraph<K, NodeData, EdgeData, NodeID, EdgeID>
+where
+    NodeID: HgNode,
+    EdgeID: HgNode,
+    NodeData: Clone,
+    EdgeData: Clone,
+{
+    /// Copies the hypergraph into an [`HGraph`] with the same IDs.
+    pub fn to_hgraph(&self) -> HGraph<NodeData, EdgeData, NodeID, EdgeID> {
+        let mut hg = HGraph::new();
+        hg.next_node_id = self.next_node_id;
+        hg.next_edge_id = self.next_edge_id;
+        for (id, node) in self.nodes.iter() {
+            hg.add_node_with_id(node.data.clone(), *id);
+        }
+        for (id, (nodes, data)) in self.edges.iter() {
+            hg.add_edge_with_id(&nodes[..], data.clone(), *id);
+        }
+        hg
+    }
+}
+
+impl<const K: usize, NodeData, EdgeData, NodeID: HgNode, EdgeID: HgNode> HyperGraph
+    for UniformHGraph<K, NodeData, EdgeData, NodeID, EdgeID>
+{
+    type NodeID = NodeID;
+    type EdgeID = EdgeID;
+
+    fn query_edge(&self, edge: &Self::EdgeID) -> Option<Vec<Self::NodeID>> {
+        self.edges.get(edge).map(|(nodes, _)| nodes.to_vec())
+    }
+
+    fn node_ids(&self) -> impl Iterator<Item = Self::NodeID> + '_ {
+        self.nodes.keys().cloned()
+    }
+
+    fn edge_ids(&self) -> impl Iterator<Item = Self::EdgeID> + '_ {
+        self.edges.keys().cloned()
+    }
+
+    fn num_nodes(&self) -> usize {
+        self.nodes.len()
+    }
+
+    fn num_edges(&self) -> usize {
+        self.edges.len()
+    }
+
+    fn degree(&self, node: &Self::NodeID) -> Option<usize> {
+        self.nodes.get(node).map(|node| node.containing_edges.len())
+    }
+
+    fn edge_size(&self, edge: &Self::EdgeID) -> Option<usize> {
+        self.edges.get(edge).map(|_| K)
+    }
+
+    fn incident_edges(&self, node: &Self::NodeID) -> Vec<Self::EdgeID> {
+        self.nodes
+            .get(node)
+            .map(|node| node.containing_edges.iter().cloned().collect())
+            .unwrap_or_default()
+    }
+
+    fn containing_edges_of_nodes(&self, nodes: impl AsRef<[Self::NodeID]>) -> Vec<Self::EdgeID> {
+        let mut query = nodes.as_ref().to_vec();
+        query.sort();
+        query.dedup();
+        if query.len() >= K {
+            return Vec::new();
+        }
+        self.supersets(&query)
+    }
+
+    /// Always empty, no edge strictly contains another of the same size.
+    fn containing_edges(&self, _edge: &Self::EdgeID) -> Vec<Self::EdgeID> {
+        Vec::new()
+    }
+
+    /// Always empty, see [`containing_edges`](UniformHGraph::containing_edges).
+    fn link(&self, _edge: &Self::EdgeID) -> Vec<(Self::EdgeID, Vec<Self::NodeID>)> {
+        Vec::new()
+    }
+
+    fn link_of_nodes(
+        &self,
+        nodes: impl AsRef<[Self::NodeID]>,
+    ) -> Vec<(Self::EdgeID, Vec<Self::NodeID>)> {
+        let query = nodes.as_ref();
+        self.containing_edges_of_nodes(query)
+            .into_iter()
+            .map(|id| {
+                let (edge, _) = self.edges.get(&id).unwrap();
+                let link = edge
+                    .iter()
+                    .filter(|node| !query.contains(node))
+                    .cloned()
+                    .collect();
+                (id, link)
+            })
+            .collect()
+    }
+
+    /// Always empty, every edge is maximal.
+    fn maximal_edges(&self, _edge_id: &Self::EdgeID) -> Vec<Self::EdgeID> {
+        Vec::new()
+    }
+
+    /// Every edge is maximal, so this is the same as
+    /// [`containing_edges_of_nodes`](UniformHGraph::containing_edges_of_nodes).
+    fn maximal_edges_of_nodes(&self, nodes: impl AsRef<[Self::NodeID]>) -> Vec<Self::EdgeID> {
+        self.containing_edges_of_nodes(nodes)
+    }
+
+    fn edges_of_size(&self, card: usize) -> Vec<Self::EdgeID> {
+        if card == K {
+            self.edges.keys().cloned().collect()
+        } else {
+            Vec::new()
+        }
+    }
+
+    fn skeleton(&self, cardinality: usize) -> Vec<Self::EdgeID> {
+        self.edges_of_size(cardinality.min(K))
+    }
+
+    /// Always empty, there are no edges of size `K + 1`.
+    fn boundary_up(&self, _edge_id: &Self::EdgeID) -> Vec<Vec<Self::NodeID>> {
+        Vec::new()
+    }
+
+    /// Always empty, there are no edges of size `K - 1`.
+    fn boundary_down(&self, _edge_id: &Self::EdgeID) -> Vec<Vec<Self::NodeID>> {
+        Vec::new()
+    }
+
+    fn boundary_up_of_nodes(&self, nodes: impl AsRef<[Self::NodeID]>) -> Vec<Vec<Self::NodeID>> {
+        let mut query = nodes.as_ref().to_vec();
+        query.sort();
+        query.dedup();
+        if query.len() + 1 != K {
+            return Vec::new();
+        }
+        self.supersets(&query)
+            .into_iter()
+            .map(|id| self.edges.get(&id).unwrap().0.to_vec())
+            .collect()
+    }
+
+    fn boundary_down_of_nodes(&self, nodes: impl AsRef<[Self::NodeID]>) -> Vec<Vec<Self::NodeID>> {
+        let query: FxHashSet<NodeID> = nodes.as_ref().iter().cloned().collect();
+        if query.len() != K + 1 {
+            return Vec::new();
+        }
+        self.subsets(&query)
+            .into_iter()
+            .map(|id| self.edges.get(&id).unwrap().0.to_vec())
+            .collect()
+    }
+
+    fn faces_of_nodes(&self, nodes: impl AsRef<[Self::NodeID]>) -> Vec<Self::EdgeID> {
+        let query: FxHashSet<NodeID> = nodes.as_ref().iter().cloned().collect();
+        if query.len() <= K {
+            return Vec::new();
+        }
+        self.subsets(&query)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use crate::{sorted, HyperGraph, UniformHGraph};
+
+    fn triangles() -> UniformHGraph<3, (), char> {
+        let mut hg = UniformHGraph::new();
+        hg.add_nodes(6);
+        hg.add_edge([0, 1, 2], 'a').unwrap();
+        hg.add_edge([1, 2, 3], 'b').unwrap();
+        hg.add_edge([3, 4, 2], 'c').unwrap();
+        hg
+    }
+
+    #[test]
+    fn rejects_wrong_sizes() {
+        let mut hg = triangles();
+        assert!(hg.add_edge([0, 1], 'x').is_err());
+        assert!(hg.add_edge([0, 1, 1], 'x').is_err());
+        assert!(hg.add_edge([0, 1, 2, 3], 'x').is_err());
+        assert!(hg.add_edge([0, 1, 9], 'x').is_err());
+        assert_eq!(hg.add_edge([2, 1, 0], 'z'), Ok(0));
+        assert_eq!(hg.get_edge(&0), Some(&'z'));
+        assert_eq!(hg.find_id([4, 3, 2]), Some(2));
+        assert_eq!(hg.edge_nodes(&2), Some(&[2, 3, 4]));
+        assert_eq!(hg.remove_node(2), Some(()));
+        assert_eq!(hg.num_edges(), 0);
+        assert_eq!(hg.degree(&1), Some(0));
+        assert_eq!(hg.add_edge([0, 1, 5], 'd'), Ok(3));
+    }
+
+    #[test]
+    fn queries_match_hgraph() {
+        let hg = triangles();
+        let copy = hg.to_hgraph();
+        for node in hg.node_ids() {
+            assert_eq!(hg.degree(&node), copy.degree(&node));
+            assert_eq!(
+                sorted(hg.link_of_nodes([node])),
+                sorted(copy.link_of_nodes([node]))
+            );
+        }
+        for nodes in [vec![], vec![1, 2], vec![2, 3], vec![0, 5]] {
+            assert_eq!(
+                sorted(hg.containing_edges_of_nodes(&nodes)),
+                sorted(copy.containing_edges_of_nodes(&nodes))
+            );
+            assert_eq!(
+                sorted(hg.boundary_up_of_nodes(&nodes)),
+                sorted(copy.boundary_up_of_nodes(&nodes))
+            );
+        }
+        assert_eq!(
+            sorted(hg.faces_of_nodes([0, 1, 2, 3])),
+            sorted(copy.faces_of_nodes([0, 1, 2, 3]))
+        );
+        assert_eq!(
+            sorted(hg.boundary_down_of_nodes([1, 2, 3, 4])),
+            vec![vec![1, 2, 3], vec![2, 3, 4]]
+        );
+        assert_eq!(sorted(hg.skeleton(5)), vec![0, 1, 2]);
+        assert!(hg.skeleton(2).is_empty());
+        assert!(hg.link(&0).is_empty());
+    }
+
+    #[test]
+    fn tensor_and_shadow() {
+        let hg = triangles();
+        let x: HashMap<u32, f64> = (0..6).map(|node| (node, node as f64 + 1.0)).collect();
+        let y = hg.tensor_contraction(&x);
+        // Node 2 is in all three triangles: 1 * 2 + 2 * 4 + 4 * 5.
+        assert_eq!(y[&2], 30.0);
+        assert_eq!(y[&0], 6.0);
+        assert_eq!(y[&5], 0.0);
+        assert_eq!(
+            hg.shadow(),
+            vec![
+                vec![0, 1],
+                vec![0, 2],
+                vec![1, 2],
+                vec![1, 3],
+                vec![2, 3],
+                vec![2, 4],
+                vec![3, 4]
+            ]
+        );
+    }
+}
@@ -0,0 +1,468 @@
+use fxhash::FxHashSet;
+
+use crate::hgraph::{Edge, Node};
+use crate::{EdgeSet, HGraph, HgNode, HyperGraph};
+
+/// A borrowed view of the edges of an `HGraph` passing a filter, along with
+/// the nodes of those edges. Created with [`HGraph::edge_filtered_view`].
+pub struct EdgeFilteredView<'a, NodeData, EdgeData, NodeID: HgNode, EdgeID: HgNode, F> {
+    graph: &'a HGraph<NodeData, EdgeData, NodeID, EdgeID>,
+    filter: F,
+}
+
+/// A borrowed view of the subhypergraph induced by a set of nodes, meaning
+/// the nodes along with every edge that only uses those nodes. Created with
+/// [`HGraph::node_induced_view`].
+pub struct NodeInducedView<'a, NodeData, EdgeData, NodeID: HgNode, EdgeID: HgNode> {
+    graph: &'a HGraph<NodeData, EdgeData, NodeID, EdgeID>,
+    nodes: FxHashSet<NodeID>,
+}
+
+/// A borrowed view of the star of a set of nodes, the edges contained in
+/// the set or strictly containing it, the same edges as [`HGraph::star`].
+/// Created with [`HGraph::star_view`].
+pub struct StarView<'a, NodeData, EdgeData, NodeID: HgNode, EdgeID: HgNode> {
+    graph: &'a HGraph<NodeData, EdgeData, NodeID, EdgeID>,
+    nodes: EdgeSet<NodeID>,
+}
+
+/// What the views have in common, the graph they borrow and which of its
+/// edges and nodes they keep.
+trait View {
+    type NodeData;
+    type EdgeData;
+    type NodeID: HgNode;
+    type EdgeID: HgNode;
+
+    fn graph(&self) -> &HGraph<Self::NodeData, Self::EdgeData, Self::NodeID, Self::EdgeID>;
+
+    fn keeps_edge(&self, edge_id: &Self::EdgeID) -> bool;
+
+    /// By default a node is kept if it is in a kept edge.
+    fn keeps_node(&self, node: &Self::NodeID) -> bool {
+        self.graph()
+            .nodes
+            .get(node)
+            .is_some_and(|node| node.containing_edges.iter().any(|id| self.keeps_edge(id)))
+    }
+}
+
+impl<'a, NodeData, EdgeData, NodeID: HgNode, EdgeID: HgNode, F> View
+    for EdgeFilteredView<'a, NodeData, EdgeData, NodeID, EdgeID, F>
+where
+    F: Fn(&EdgeID) -> bool,
+{
+    type NodeData = NodeData;
+    type EdgeData = EdgeData;
+    type NodeID = NodeID;
+    type EdgeID = EdgeID;
+
+    fn graph(&self) -> &HGraph<NodeData, EdgeData, NodeID, EdgeID> {
+        self.graph
+    }
+
+    fn keeps_edge(&self, edge_id: &EdgeID) -> bool {
+        self.graph.edges.contains_key(edge_id) && (self.filter)(edge_id)
+    }
+}
+
+impl<'a, NodeData, EdgeData, NodeID: HgNode, EdgeID: HgNode> View
+    for NodeInducedView<'a, NodeData, EdgeData, NodeID, EdgeID>
+{
+    type NodeData = NodeData;
+    type EdgeData = EdgeData;
+    type NodeID = NodeID;
+    type EdgeID = EdgeID;
+
+    fn graph(&self) -> &HGraph<NodeData, EdgeData, NodeID, EdgeID> {
+        self.graph
+    }
+
+    fn keeps_edge(&self, edge_id: &EdgeID) -> bool {
+        self.graph
+            .edges
+            .get(edge_id)
+            .is_some_and(|edge| edge.nodes.0.iter().all(|node| self.nodes.contains(node)))
+    }
+
+    fn keeps_node(&self, node: &NodeID) -> bool {
+        self.nodes.contains(node)
+    }
+}
+
+impl<'a, NodeData, EdgeData, NodeID: HgNode, EdgeID: HgNode> View
+    for StarView<'a, NodeData, EdgeData, NodeID, EdgeID>
+{
+    type NodeData = NodeData;
+    type EdgeData = EdgeData;
+    type NodeID = NodeID;
+    type EdgeID = EdgeID;
+
+    fn graph(&self) -> &HGraph<NodeData, EdgeData, NodeID, EdgeID> {
+        self.graph
+    }
+
+    fn keeps_edge(&self, edge_id: &EdgeID) -> bool {
+        self.graph.edges.get(edge_id).is_some_and(|edge| {
+            self.nodes.contains(&edge.nodes) || edge.nodes.contains_strict(&self.nodes)
+        })
+    }
+}
+
+fn edge_nodes<'v, V: View>(view: &'v V, edge_id: &V::EdgeID) -> Option<&'v EdgeSet<V::NodeID>> {
+    if view.keeps_edge(edge_id) {
+        view.graph().edges.get(edge_id).map(|edge| &edge.nodes)
+    } else {
+        None
+    }
+}
+
+fn kept<V: View>(view: &V, edges: Vec<V::EdgeID>) -> Vec<V::EdgeID> {
+    edges.into_iter().filter(|id| view.keeps_edge(id)).collect()
+}
+
+fn maximal_among<V: View>(view: &V, containing: Vec<V::EdgeID>) -> Vec<V::EdgeID> {
+    containing
+        .into_iter()
+        .filter(|id| {
+            let above = view.graph().containing_edges(id);
+            !above.iter().any(|other| view.keeps_edge(other))
+        })
+        .collect()
+}
+
+fn edges_of_len<V: View>(view: &V, edges: Vec<V::EdgeID>, len: usize) -> Vec<Vec<V::NodeID>> {
+    edges
+        .into_iter()
+        .filter_map(|id| edge_nodes(view, &id))
+        .filter(|nodes| nodes.len() == len)
+        .map(|nodes| nodes.node_vec())
+        .collect()
+}
+
+/// Implements `HyperGraph` for a view by running each query on the
+/// underlying graph and dropping whatever the view does not keep.
+macro_rules! view_hypergraph_methods {
+    () => {
+        fn query_edge(&self, edge: &Self::EdgeID) -> Option<Vec<Self::NodeID>> {
+            edge_nodes(self, edge).map(|nodes| nodes.node_vec())
+        }
+
+        fn node_ids(&self) -> impl Iterator<Item = Self::NodeID> + '_ {
+            self.graph
+                .nodes
+                .keys()
+                .filter(|node| self.keeps_node(node))
+                .cloned()
+        }
+
+        fn edge_ids(&self) -> impl Iterator<Item = Self::EdgeID> + '_ {
+            self.graph
+                .edges
+                .keys()
+                .filter(|id| self.keeps_edge(id))
+                .cloned()
+        }
+
+        fn degree(&self, node: &Self::NodeID) -> Option<usize> {
+            if !self.keeps_node(node) {
+                return None;
+            }
+            Some(self.incident_edges(node).len())
+        }
+
+        fn incident_edges(&self, node: &Self::NodeID) -> Vec<Self::EdgeID> {
+            if !self.keeps_node(node) {
+                return Vec::new();
+            }
+            kept(self, self.graph.incident_edges(node))
+        }
+
+        fn containing_edges_of_nodes(
+            &self,
+            nodes: impl AsRef<[Self::NodeID]>,
+        ) -> Vec<Self::EdgeID> {
+            kept(self, self.graph.containing_edges_of_nodes(nodes))
+        }
+
+        fn containing_edges(&self, edge: &Self::EdgeID) -> Vec<Self::EdgeID> {
+            if !self.keeps_edge(edge) {
+                return Vec::new();
+            }
+            kept(self, self.graph.containing_edges(edge))
+        }
+
+        fn link(&self, edge: &Self::EdgeID) -> Vec<(Self::EdgeID, Vec<Self::NodeID>)> {
+            if !self.keeps_edge(edge) {
+                return Vec::new();
+            }
+            self.graph
+                .link(edge)
+                .into_iter()
+                .filter(|(id, _)| self.keeps_edge(id))
+                .collect()
+        }
+
+        fn link_of_nodes(
+            &self,
+            nodes: impl AsRef<[Self::NodeID]>,
+        ) -> Vec<(Self::EdgeID, Vec<Self::NodeID>)> {
+            self.graph
+                .link_of_nodes(nodes)
+                .into_iter()
+                .filter(|(id, _)| self.keeps_edge(id))
+                .collect()
+        }
+
+        fn maximal_edges(&self, edge_id: &Self::EdgeID) -> Vec<Self::EdgeID> {
+            maximal_among(self, self.containing_edges(edge_id))
+        }
+
+        fn maximal_edges_of_nodes(&self, nodes: impl AsRef<[Self::NodeID]>) -> Vec<Self::EdgeID> {
+            maximal_among(self, self.containing_edges_of_nodes(nodes))
+        }
+
+        fn edges_of_size(&self, card: usize) -> Vec<Self::EdgeID> {
+            kept(self, self.graph.edges_of_size(card))
+        }
+
+        fn skeleton(&self, cardinality: usize) -> Vec<Self::EdgeID> {
+            kept(self, self.graph.skeleton(cardinality))
+        }
+
+        fn boundary_up(&self, edge_id: &Self::EdgeID) -> Vec<Vec<Self::NodeID>> {
+            let Some(edge) = edge_nodes(self, edge_id) else {
+                return Vec::new();
+            };
+            edges_of_len(self, self.containing_edges(edge_id), edge.len() + 1)
+        }
+
+        fn boundary_down(&self, edge_id: &Self::EdgeID) -> Vec<Vec<Self::NodeID>> {
+            let Some(edge) = edge_nodes(self, edge_id) else {
+                return Vec::new();
+            };
+            edges_of_len(self, self.faces(edge_id), edge.len().saturating_sub(1))
+        }
+
+        fn boundary_up_of_nodes(
+            &self,
+            nodes: impl AsRef<[Self::NodeID]>,
+        ) -> Vec<Vec<Self::NodeID>> {
+            let len = EdgeSet::from(nodes.as_ref()).len();
+            edges_of_len(self, self.containing_edges_of_nodes(nodes), len + 1)
+        }
+
+        fn boundary_down_of_nodes(
+            &self,
+            nodes: impl AsRef<[Self::NodeID]>,
+        ) -> Vec<Vec<Self::NodeID>> {
+            let len = EdgeSet::from(nodes.as_ref()).len();
+            edges_of_len(self, self.faces_of_nodes(nodes), len.saturating_sub(1))
+        }
+
+        fn faces_of_nodes(&self, nodes: impl AsRef<[Self::NodeID]>) -> Vec<Self::EdgeID> {
+            kept(self, self.graph.faces_of_nodes(nodes))
+        }
+    };
+}
+
+impl<'a, NodeData, EdgeData, NodeID: HgNode, EdgeID: HgNode, F> HyperGraph
+    for EdgeFilteredView<'a, NodeData, EdgeData, NodeID, EdgeID, F>
+where
+    F: Fn(&EdgeID) -> bool,
+{
+    type NodeID = NodeID;
+    type EdgeID = EdgeID;
+
+    view_hypergraph_methods!();
+}
+
+impl<'a, NodeData, EdgeData, NodeID: HgNode, EdgeID: HgNode> HyperGraph
+    for NodeInducedView<'a, NodeData, EdgeData, NodeID, EdgeID>
+{
+    type NodeID = NodeID;
+    type EdgeID = EdgeID;
+
+    view_hypergraph_methods!();
+}
+
+impl<'a, NodeData, EdgeData, NodeID: HgNode, EdgeID: HgNode> HyperGraph
+    for StarView<'a, NodeData, EdgeData, NodeID, EdgeID>
+{
+    type NodeID = NodeID;
+    type EdgeID = EdgeID;
+
+    view_hypergraph_methods!();
+}
+
+/// Copies the kept nodes and edges, with their data and IDs, into a new
+/// `HGraph`. The new graph continues handing out IDs where the borrowed
+/// graph would have.
+fn materialize<V>(view: &V) -> HGraph<V::NodeData, V::EdgeData, V::NodeID, V::EdgeID>
+where
+    V: View,
+    V::NodeData: Clone,
+    V::EdgeData: Clone,
+{
+    let graph = view.graph();
+    let mut owned = HGraph::new();
+    owned.next_node_id = graph.next_node_id;
+    owned.next_edge_id = graph.next_edge_id;
+    owned.node_alloc = graph.node_alloc.clone();
+    owned.edge_alloc = graph.edge_alloc.clone();
+    for (id, edge) in graph.edges.iter() {
+        if view.keeps_edge(id) {
+            owned.edges.insert(
+                *id,
+                Edge {
+                    nodes: edge.nodes.clone(),
+                    data: edge.data.clone(),
+                },
+            );
+        }
+    }
+    for (id, node) in graph.nodes.iter() {
+        if view.keeps_node(id) {
+            let containing_edges = node
+                .containing_edges
+                .iter()
+                .filter(|edge_id| owned.edges.contains_key(edge_id))
+                .cloned()
+                .collect();
+            owned.nodes.insert(
+                *id,
+                Node {
+                    containing_edges,
+                    data: node.data.clone(),
+                },
+            );
+        }
+    }
+    owned
+}
+
+macro_rules! view_to_hgraph {
+    ($view:ident $(, $filter:ident)?) => {
+        impl<'a, NodeData, EdgeData, NodeID: HgNode, EdgeID: HgNode $(, $filter)?>
+            $view<'a, NodeData, EdgeData, NodeID, EdgeID $(, $filter)?>
+        where
+            NodeData: Clone,
+            EdgeData: Clone,
+            $($filter: Fn(&EdgeID) -> bool,)?
+        {
+            /// Copies the nodes and edges of the view, keeping their IDs and
+            /// data, into an owned `HGraph`.
+            pub fn to_hgraph(&self) -> HGraph<NodeData, EdgeData, NodeID, EdgeID> {
+                materialize(self)
+            }
+        }
+    };
+}
+
+view_to_hgraph!(EdgeFilteredView, F);
+view_to_hgraph!(NodeInducedView);
+view_to_hgraph!(StarView);
+
+impl<NodeData, EdgeData, NodeID: HgNode, EdgeID: HgNode>
+    HGraph<NodeData, EdgeData, NodeID, EdgeID>
+{
+    /// Borrows the edges passing `filter` along with their nodes, the view
+    /// counterpart of [`filter_by_edge`](HGraph::filter_by_edge).
+    pub fn edge_filtered_view<F>(
+        &self,
+        filter: F,
+    ) -> EdgeFilteredView<'_, NodeData, EdgeData, NodeID, EdgeID, F>
+    where
+        F: Fn(&EdgeID) -> bool,
+    {
+        EdgeFilteredView {
+            graph: self,
+            filter,
+        }
+    }
+
+    /// Borrows the provided nodes along with every edge that only uses them.
+    /// Nodes that are not present are ignored.
+    pub fn node_induced_view(
+        &self,
+        nodes: impl AsRef<[NodeID]>,
+    ) -> NodeInducedView<'_, NodeData, EdgeData, NodeID, EdgeID> {
+        NodeInducedView {
+            graph: self,
+            nodes: nodes
+                .as_ref()
+                .iter()
+                .filter(|node| self.nodes.contains_key(node))
+                .cloned()
+                .collect(),
+        }
+    }
+
+    /// Borrows the star of the provided nodes, the view counterpart of
+    /// [`star`](HGraph::star).
+    pub fn star_view(
+        &self,
+        nodes: impl AsRef<[NodeID]>,
+    ) -> StarView<'_, NodeData, EdgeData, NodeID, EdgeID> {
+        StarView {
+            graph: self,
+            nodes: nodes.into(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{sorted, HGraph, HyperGraph};
+
+    fn graph() -> HGraph<u8, char> {
+        let mut hg = HGraph::new();
+        for x in 0..6 {
+            hg.add_node(x);
+        }
+        hg.add_edge([0, 1], 'a');
+        hg.add_edge([0, 1, 2], 'b');
+        hg.add_edge([0, 1, 2, 3], 'c');
+        hg.add_edge([3, 4], 'd');
+        hg
+    }
+
+    #[test]
+    fn views_filter_queries() {
+        let hg = graph();
+        let small = hg.edge_filtered_view(|id| *id != 2);
+        assert_eq!(sorted(small.node_ids().collect()), vec![0, 1, 2, 3, 4]);
+        assert_eq!(small.maximal_edges(&0), vec![1]);
+        assert_eq!(small.boundary_up(&0), vec![vec![0, 1, 2]]);
+        assert_eq!(small.degree(&3), Some(1));
+        assert_eq!(small.degree(&5), None);
+
+        let induced = hg.node_induced_view([0, 1, 2, 5]);
+        assert_eq!(sorted(induced.edge_ids().collect()), vec![0, 1]);
+        assert_eq!(sorted(induced.node_ids().collect()), vec![0, 1, 2, 5]);
+        assert_eq!(induced.degree(&5), Some(0));
+        assert_eq!(induced.query_edge(&3), None);
+        assert_eq!(induced.boundary_down(&1), vec![vec![0, 1]]);
+
+        let star = hg.star_view([0, 1]);
+        assert_eq!(
+            sorted(star.edge_ids().collect()),
+            sorted(hg.star([0, 1]).edges())
+        );
+        assert_eq!(star.containing_edges_of_nodes([3]), vec![2]);
+    }
+
+    #[test]
+    fn materialize_keeps_ids_and_data() {
+        let mut hg = graph();
+        let induced = hg.node_induced_view([0, 1, 2, 5]).to_hgraph();
+        assert_eq!(induced.get_edge(&1), Some(&'b'));
+        assert_eq!(induced.get_node(&5), Some(&5));
+        assert_eq!(induced.num_edges(), 2);
+
+        let mut owned = hg.edge_filtered_view(|id| *id == 3).to_hgraph();
+        assert_eq!(sorted(owned.nodes()), vec![3, 4]);
+        // New IDs do not collide with the ones of the original graph.
+        assert_eq!(owned.add_node(9), hg.add_node(9));
+    }
+}